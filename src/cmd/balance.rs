@@ -66,7 +66,7 @@ fn print_results(results: Vec<(String, Result<Account>)>, format: OutputFormat)
             }
             print_table(&table)
         }
-        OutputFormat::Json => {
+        OutputFormat::Json | OutputFormat::Csv | OutputFormat::Yaml => {
             let mut rows = Vec::with_capacity(results.len());
             for (address, result) in results {
                 if let Ok(account) = result {
@@ -69,7 +69,13 @@ impl Cmd {
 
         let envelope = txn.in_envelope();
         let status = maybe_submit_txn(self.commit, &client, &envelope).await?;
-        print_txn(&txn, &envelope, &status, opts.format)
+        print_txn(
+            &txn,
+            &envelope,
+            &status,
+            wallet.public_key.network,
+            opts.format,
+        )
     }
 }
 
@@ -77,6 +83,7 @@ fn print_txn(
     txn: &BlockchainTxnTokenBurnV1,
     envelope: &BlockchainTxn,
     status: &Option<PendingTxnStatus>,
+    network: Network,
     format: OutputFormat,
 ) -> Result {
     match format {
@@ -90,9 +97,9 @@ fn print_txn(
                 ["Nonce", txn.nonce],
                 ["Hash", status_str(status)]
             );
-            print_footer(status)
+            print_footer(status, network)
         }
-        OutputFormat::Json => {
+        OutputFormat::Json | OutputFormat::Csv | OutputFormat::Yaml => {
             let table = json!({
                 "payee": PublicKey::from_bytes(&txn.payee)?.to_string(),
                 "amount": Hnt::from(txn.amount),
@@ -100,6 +107,7 @@ fn print_txn(
                 "fee": txn.fee,
                 "nonce": txn.nonce,
                 "hash": status_json(status),
+                "explorer_url": status_explorer_json(status, network),
                 "txn": envelope.to_b64()?
             });
             print_json(&table)
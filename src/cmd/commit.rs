@@ -21,24 +21,26 @@ impl Cmd {
         let client = new_client(api_url(wallet.public_key.network));
 
         let status = maybe_submit_txn(true, &client, &envelope).await?;
-        print_txn(&envelope, &status, opts.format)
+        print_txn(&envelope, &status, wallet.public_key.network, opts.format)
     }
 }
 
 fn print_txn(
     envelope: &BlockchainTxn,
     status: &Option<PendingTxnStatus>,
+    network: Network,
     format: OutputFormat,
 ) -> Result {
     match format {
         OutputFormat::Table => {
             ptable!(["Key", "Value"], ["Hash", status_str(status)]);
 
-            print_footer(status)
+            print_footer(status, network)
         }
-        OutputFormat::Json => {
+        OutputFormat::Json | OutputFormat::Csv | OutputFormat::Yaml => {
             let table = json!({
                 "hash": status_json(status),
+                "explorer_url": status_explorer_json(status, network),
                 "txn": envelope.to_b64()?
             });
 
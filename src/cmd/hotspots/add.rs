@@ -57,13 +57,14 @@ impl Cmd {
         }?;
 
         let status = maybe_submit_txn(self.commit, &client, &envelope).await?;
-        print_txn(&txn, &status, opts.format)
+        print_txn(&txn, &status, wallet.public_key.network, opts.format)
     }
 }
 
 fn print_txn(
     txn: &BlockchainTxnAddGatewayV1,
     status: &Option<PendingTxnStatus>,
+    network: Network,
     format: OutputFormat,
 ) -> Result {
     let address = PublicKey::from_bytes(&txn.gateway)?.to_string();
@@ -84,9 +85,9 @@ fn print_txn(
                 ["Staking fee (DC)", txn.staking_fee],
                 ["Hash", status_str(status)]
             );
-            print_footer(status)
+            print_footer(status, network)
         }
-        OutputFormat::Json => {
+        OutputFormat::Json | OutputFormat::Csv | OutputFormat::Yaml => {
             let table = json!({
                 "address": address,
                 "owner": owner,
@@ -94,6 +95,7 @@ fn print_txn(
                 "fee": txn.fee,
                 "staking fee": txn.staking_fee,
                 "hash": status_json(status),
+                "explorer_url": status_explorer_json(status, network),
                 "txn": txn.in_envelope().to_b64()?
             });
             print_json(&table)
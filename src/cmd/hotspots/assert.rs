@@ -139,7 +139,13 @@ impl Cmd {
         }?;
 
         let status = maybe_submit_txn(self.commit, &client, &envelope).await?;
-        print_txn(&txn, &envelope, &status, opts.format)
+        print_txn(
+            &txn,
+            &envelope,
+            &status,
+            wallet.public_key.network,
+            opts.format,
+        )
     }
 }
 
@@ -147,6 +153,7 @@ fn print_txn(
     txn: &BlockchainTxnAssertLocationV2,
     envelope: &BlockchainTxn,
     status: &Option<PendingTxnStatus>,
+    network: Network,
     format: OutputFormat,
 ) -> Result {
     let address = PublicKey::from_bytes(&txn.gateway)?.to_string();
@@ -169,9 +176,9 @@ fn print_txn(
                 ["Elevation", txn.elevation],
                 ["Hash", status_str(status)]
             );
-            print_footer(status)
+            print_footer(status, network)
         }
-        OutputFormat::Json => {
+        OutputFormat::Json | OutputFormat::Csv | OutputFormat::Yaml => {
             let table = json!({
                 "address": address,
                 "location": txn.location,
@@ -182,6 +189,7 @@ fn print_txn(
                 "nonce": txn.nonce,
                 "staking_fee": txn.staking_fee,
                 "hash": status_json(status),
+                "explorer_url": status_explorer_json(status, network),
                 "txn": envelope.to_b64()?,
             });
             print_json(&table)
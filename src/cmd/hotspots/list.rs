@@ -75,7 +75,7 @@ fn print_results(results: Vec<(PublicKey, Result<Vec<Hotspot>>)>, format: Output
             }
             print_table(&table)
         }
-        OutputFormat::Json => {
+        OutputFormat::Json | OutputFormat::Csv | OutputFormat::Yaml => {
             let mut table = Vec::with_capacity(results.len());
             for (address, result) in results {
                 let mut table_hotspots = vec![];
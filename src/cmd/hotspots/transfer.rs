@@ -41,7 +41,13 @@ impl Cmd {
 
         let envelope = txn.in_envelope();
         let status = maybe_submit_txn(self.commit, &client, &envelope).await?;
-        print_txn(&txn, &envelope, &status, opts.format)
+        print_txn(
+            &txn,
+            &envelope,
+            &status,
+            wallet.public_key.network,
+            opts.format,
+        )
     }
 }
 
@@ -49,6 +55,7 @@ fn print_txn(
     txn: &BlockchainTxnTransferHotspotV2,
     envelope: &BlockchainTxn,
     status: &Option<PendingTxnStatus>,
+    network: Network,
     format: OutputFormat,
 ) -> Result {
     let address = PublicKey::from_bytes(&txn.gateway)?.to_string();
@@ -63,15 +70,16 @@ fn print_txn(
                 ["Fee (DC)", txn.fee],
                 ["Hash", status_str(status)]
             );
-            print_footer(status)
+            print_footer(status, network)
         }
-        OutputFormat::Json => {
+        OutputFormat::Json | OutputFormat::Csv | OutputFormat::Yaml => {
             let table = json!({
                 "address": address,
                 "new_owner": new_owner,
                 "fee": txn.fee,
                 "nonce": txn.nonce,
                 "hash": status_json(status),
+                "explorer_url": status_explorer_json(status, network),
                 "txn": envelope.to_b64()?,
             });
             print_json(&table)
@@ -89,7 +89,13 @@ impl Create {
         let envelope = txn.in_envelope();
 
         let status = maybe_submit_txn(self.commit, &client, &envelope).await?;
-        print_create_txn(&txn, &envelope, &status, opts.format)
+        print_create_txn(
+            &txn,
+            &envelope,
+            &status,
+            wallet.public_key.network,
+            opts.format,
+        )
     }
 }
 
@@ -97,6 +103,7 @@ fn print_create_txn(
     txn: &BlockchainTxnCreateHtlcV1,
     envelope: &BlockchainTxn,
     status: &Option<PendingTxnStatus>,
+    network: Network,
     format: OutputFormat,
 ) -> Result {
     match format {
@@ -112,9 +119,9 @@ fn print_create_txn(
                 ["Nonce", txn.nonce],
                 ["Hash", status_str(status)]
             );
-            print_footer(status)
+            print_footer(status, network)
         }
-        OutputFormat::Json => {
+        OutputFormat::Json | OutputFormat::Csv | OutputFormat::Yaml => {
             let table = json!({
                 "address": PublicKey::from_bytes(&txn.address)?.to_string(),
                 "payee": PublicKey::from_bytes(&txn.payee)?.to_string(),
@@ -124,6 +131,7 @@ fn print_create_txn(
                 "timelock": txn.timelock,
                 "nonce": txn.nonce,
                 "hash": status_json(status),
+                "explorer_url": status_explorer_json(status, network),
                 "txn": envelope.to_b64()?,
             });
             print_json(&table)
@@ -150,7 +158,13 @@ impl Redeem {
 
         let envelope = txn.in_envelope();
         let status = maybe_submit_txn(self.commit, &client, &envelope).await?;
-        print_redeem_txn(&txn, &envelope, &status, opts.format)
+        print_redeem_txn(
+            &txn,
+            &envelope,
+            &status,
+            wallet.public_key.network,
+            opts.format,
+        )
     }
 }
 
@@ -158,6 +172,7 @@ fn print_redeem_txn(
     txn: &BlockchainTxnRedeemHtlcV1,
     envelope: &BlockchainTxn,
     status: &Option<PendingTxnStatus>,
+    network: Network,
     format: OutputFormat,
 ) -> Result {
     match format {
@@ -169,13 +184,14 @@ fn print_redeem_txn(
                 ["Preimage", std::str::from_utf8(&txn.preimage)?],
                 ["Hash", status_str(status)]
             );
-            print_footer(status)
+            print_footer(status, network)
         }
-        OutputFormat::Json => {
+        OutputFormat::Json | OutputFormat::Csv | OutputFormat::Yaml => {
             let table = json!({
                 "address": PublicKey::from_bytes(&txn.address)?.to_string(),
                 "payee": PublicKey::from_bytes(&txn.payee)?.to_string(),
                 "hash": status_json(status),
+                "explorer_url": status_explorer_json(status, network),
                 "txn": envelope.to_b64()?,
             });
             print_json(&table)
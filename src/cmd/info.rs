@@ -45,7 +45,7 @@ fn print_public_key(public_key: &PublicKey, format: OutputFormat) -> Result {
             table.add_row(row!["Type", public_key.key_tag().key_type]);
             print_table(&table)
         }
-        OutputFormat::Json => {
+        OutputFormat::Json | OutputFormat::Csv | OutputFormat::Yaml => {
             let table = json!({
                 "network": public_key.key_tag().network.to_string(),
                 "type": public_key.key_tag().key_type.to_string(),
@@ -70,7 +70,7 @@ fn print_wallet(wallet: &Wallet, account: &Account, format: OutputFormat) -> Res
             table.add_row(row!["Securities Balance", account.sec_balance]);
             print_table(&table)
         }
-        OutputFormat::Json => {
+        OutputFormat::Json | OutputFormat::Csv | OutputFormat::Yaml => {
             let table = json!({
                 "sharded": wallet.is_sharded(),
                 "network": wallet.public_key.key_tag().network.to_string(),
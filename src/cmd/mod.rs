@@ -1,7 +1,7 @@
 use crate::{
     keypair::{Network, PublicKey},
     mnemonic,
-    result::{bail, Error, Result},
+    result::{anyhow, bail, Error, Result},
     traits::{TxnFeeConfig, B64},
     wallet::Wallet,
 };
@@ -13,8 +13,11 @@ pub use helium_proto::*;
 pub use serde_json::json;
 
 use std::{
-    env, fs, io,
+    env,
+    ffi::OsString,
+    fmt, fs, io,
     path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
 };
 pub use structopt::{clap::arg_enum, StructOpt};
 
@@ -41,6 +44,8 @@ arg_enum! {
     pub enum OutputFormat {
         Table,
         Json,
+        Csv,
+        Yaml,
     }
 }
 
@@ -56,12 +61,212 @@ pub struct Opts {
     )]
     files: Vec<PathBuf>,
 
-    /// Output format to use
+    /// Output format to use. Commands without csv or yaml support print json
+    /// for those formats.
     #[structopt(long = "format",
-                possible_values = &["table", "json"],
+                possible_values = &["table", "json", "csv", "yaml"],
                 case_insensitive = true,
                 default_value = "table")]
     format: OutputFormat,
+
+    /// API URL to use instead of the one given by HELIUM_API_URL or
+    /// HELIUM_TESTNET_API_URL. Used by the oracle commands. May be repeated
+    /// or comma separated, in which case oracle reports fall over to the
+    /// next URL when one fails.
+    #[structopt(long, number_of_values = 1, use_delimiter = true)]
+    api_url: Vec<String>,
+
+    /// Print diagnostics, such as every failed price fetch attempt, to
+    /// stderr
+    #[structopt(long)]
+    verbose: bool,
+
+    /// Print json output on a single line instead of pretty printed
+    #[structopt(long)]
+    compact: bool,
+
+    /// Do not print informational messages, such as a report being
+    /// skipped or cleared, to stderr
+    #[structopt(long)]
+    quiet: bool,
+
+    /// Use the testnet API, and link to the testnet explorer, even for a
+    /// mainnet wallet. Used by the oracle commands. An --api-url still takes
+    /// precedence for the API.
+    #[structopt(long)]
+    testnet: bool,
+
+    /// TOML file of options to use, keyed by their long name. Global options
+    /// such as "file" go at the top, and options of a subcommand under its
+    /// table, for example "retry-attempts = 3" under [oracle.report].
+    /// Options given on the command line take precedence.
+    // Only declared for --help and parsing. config_args reads the file from
+    // the raw arguments before they are parsed.
+    #[allow(dead_code)]
+    #[structopt(long)]
+    config: Option<PathBuf>,
+}
+
+impl Opts {
+    /// Applies the options that affect the output of every command. Call
+    /// before running a command.
+    pub fn apply_output(&self) {
+        COMPACT_JSON.store(self.compact, Ordering::Relaxed);
+        QUIET.store(self.quiet, Ordering::Relaxed);
+    }
+
+    /// The network to use, which is testnet if --testnet is given
+    fn network(&self, network: Network) -> Network {
+        if self.testnet {
+            Network::TestNet
+        } else {
+            network
+        }
+    }
+
+    /// The API URL to use for the given network, preferring the first
+    /// --api-url
+    fn api_url(&self, network: Network) -> String {
+        self.api_url
+            .first()
+            .cloned()
+            .unwrap_or_else(|| api_url(self.network(network)))
+    }
+
+    /// The API URLs to use in order for the given network, preferring
+    /// --api-url
+    fn api_urls(&self, network: Network) -> Vec<String> {
+        if self.api_url.is_empty() {
+            vec![api_url(self.network(network))]
+        } else {
+            self.api_url.clone()
+        }
+    }
+}
+
+/// Options that are given before the subcommand
+const TOP_LEVEL_OPTS: &[&str] = &[
+    "file", "format", "api-url", "verbose", "compact", "quiet", "testnet",
+];
+
+/// Adds the options in the --config file, if one is given, to the given
+/// command line arguments.
+pub fn config_args(args: Vec<OsString>) -> Result<Vec<OsString>> {
+    let path = args.iter().enumerate().find_map(|(i, arg)| {
+        let arg = arg.to_str()?;
+        match arg.strip_prefix("--config=") {
+            Some(path) => Some(PathBuf::from(path)),
+            None if arg == "--config" => args.get(i + 1).map(PathBuf::from),
+            None => None,
+        }
+    });
+    match path {
+        Some(path) => {
+            let contents = fs::read_to_string(&path)?;
+            let config: toml::value::Table = toml::from_str(&contents)
+                .map_err(|err| anyhow!("Invalid config file {}: {}", path.display(), err))?;
+            merge_config_args(args, &config)
+        }
+        None => Ok(args),
+    }
+}
+
+/// Short aliases of options, so that a config option is also recognized as
+/// given on the command line in its short form
+const SHORT_OPTS: &[(&str, &str)] = &[
+    ("file", "-f"),
+    ("output", "-o"),
+    ("shards", "-n"),
+    ("required-shards", "-k"),
+    ("address", "-a"),
+    ("preimage", "-p"),
+];
+
+/// Whether the option with the given long name is given in args, in its
+/// long or short form
+fn is_given(args: &[OsString], key: &str) -> bool {
+    let flag = format!("--{}", key);
+    let short = SHORT_OPTS
+        .iter()
+        .find(|(long, _)| *long == key)
+        .map(|(_, short)| *short);
+    args.iter().any(|arg| {
+        arg.to_str().is_some_and(|arg| {
+            arg == flag
+                || arg.starts_with(&format!("{}=", flag))
+                || short.is_some_and(|short| arg.starts_with(short))
+        })
+    })
+}
+
+/// Expands a config option to its command line arguments
+fn config_option_args(key: &str, value: &toml::Value) -> Result<Vec<OsString>> {
+    let flag = format!("--{}", key);
+    let values = match value {
+        toml::Value::Array(values) => values.iter().collect(),
+        value => vec![value],
+    };
+    let mut expanded = Vec::new();
+    for value in values {
+        match value {
+            toml::Value::Boolean(true) => expanded.push(flag.clone()),
+            toml::Value::Boolean(false) => (),
+            toml::Value::String(s) => expanded.extend([flag.clone(), s.clone()]),
+            toml::Value::Integer(_) | toml::Value::Float(_) => {
+                expanded.extend([flag.clone(), value.to_string()])
+            }
+            _ => bail!("Unsupported value for config option {}", key),
+        }
+    }
+    Ok(expanded.into_iter().map(OsString::from).collect())
+}
+
+/// Adds the given config options that are not already given in args. Global
+/// options at the top of the config are inserted before the subcommand. The
+/// options in the table of the subcommand being run, such as
+/// [oracle.report], are appended, and the tables of other subcommands are
+/// ignored.
+fn merge_config_args(
+    mut args: Vec<OsString>,
+    config: &toml::value::Table,
+) -> Result<Vec<OsString>> {
+    let mut top_level = Vec::new();
+    for (key, value) in config {
+        if value.is_table() {
+            continue;
+        }
+        if !TOP_LEVEL_OPTS.contains(&key.as_str()) {
+            bail!(
+                "Config option {} must be under the table of the command it applies to, \
+                 for example [oracle.report]",
+                key
+            );
+        }
+        if !is_given(&args, key) {
+            top_level.extend(config_option_args(key, value)?);
+        }
+    }
+
+    let mut table = None;
+    for arg in args.iter().skip(1) {
+        let tables = table.unwrap_or(config);
+        if let Some(toml::Value::Table(subcommand)) = arg.to_str().and_then(|arg| tables.get(arg)) {
+            table = Some(subcommand);
+        }
+    }
+    let mut subcommand = Vec::new();
+    if let Some(table) = table {
+        for (key, value) in table {
+            if !value.is_table() && !is_given(&args, key) {
+                subcommand.extend(config_option_args(key, value)?);
+            }
+        }
+    }
+    args.extend(subcommand);
+
+    let at = args.len().min(1);
+    args.splice(at..at, top_level);
+    Ok(args)
 }
 
 #[derive(Debug, Clone)]
@@ -96,6 +301,11 @@ fn load_wallet(files: Vec<PathBuf>) -> Result<Wallet> {
 
 fn get_password(confirm: bool) -> std::io::Result<String> {
     match env::var("HELIUM_WALLET_PASSWORD") {
+        Ok(str) if str.is_empty() => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "HELIUM_WALLET_PASSWORD is set but empty. Unset it to be prompted instead; \
+             note that a password in the environment is visible to the same user's processes",
+        )),
         Ok(str) => Ok(str),
         _ => {
             use dialoguer::Password;
@@ -109,6 +319,23 @@ fn get_password(confirm: bool) -> std::io::Result<String> {
     }
 }
 
+/// Reads a wallet password from the first line of the given file. On unix
+/// the file must not be readable by other users.
+fn read_password_file(path: &Path) -> Result<String> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if fs::metadata(path)?.permissions().mode() & 0o004 != 0 {
+            bail!(
+                "Password file {} is world-readable, restrict its permissions",
+                path.display()
+            );
+        }
+    }
+    let contents = fs::read_to_string(path)?;
+    Ok(contents.lines().next().unwrap_or_default().to_string())
+}
+
 const DEFAULT_TESTNET_BASE_URL: &str = "https://testnet-api.helium.wtf/v1";
 
 fn api_url(network: Network) -> String {
@@ -215,15 +442,56 @@ pub fn get_file_extension(filename: &Path) -> String {
         .to_string()
 }
 
-pub fn print_footer(status: &Option<PendingTxnStatus>) -> Result {
-    if status.is_none() {
-        println!("\nPreview mode: use --commit to submit the transaction to the network");
+const DEFAULT_EXPLORER_URL: &str = "https://explorer.helium.com";
+const DEFAULT_TESTNET_EXPLORER_URL: &str = "https://testnet-explorer.helium.wtf";
+
+/// The explorer link for a transaction submitted to the given network
+pub fn explorer_url(network: Network, hash: &str) -> String {
+    let base_url = match network {
+        Network::MainNet => {
+            env::var("HELIUM_EXPLORER_URL").unwrap_or_else(|_| DEFAULT_EXPLORER_URL.to_string())
+        }
+        Network::TestNet => env::var("HELIUM_TESTNET_EXPLORER_URL")
+            .unwrap_or_else(|_| DEFAULT_TESTNET_EXPLORER_URL.to_string()),
+    };
+    format!("{}/txns/{}", base_url.trim_end_matches('/'), hash)
+}
+
+pub fn print_footer(status: &Option<PendingTxnStatus>, network: Network) -> Result {
+    match status {
+        Some(status) => println!("\nExplorer: {}", explorer_url(network, &status.hash)),
+        None => {
+            println!("\nPreview mode: use --commit to submit the transaction to the network")
+        }
     };
     Ok(())
 }
 
+/// Whether print_json prints compact json, as set by --compact
+static COMPACT_JSON: AtomicBool = AtomicBool::new(false);
+
+/// Whether print_info is silenced, as set by --quiet
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Prints an informational message to stderr, keeping stdout for the
+/// command's output, unless --quiet is given
+pub fn print_info(message: fmt::Arguments) {
+    if !QUIET.load(Ordering::Relaxed) {
+        eprintln!("{}", message);
+    }
+}
+
 pub fn print_json<T: ?Sized + serde::Serialize>(value: &T) -> Result {
-    println!("{}", serde_json::to_string_pretty(value)?);
+    if COMPACT_JSON.load(Ordering::Relaxed) {
+        println!("{}", serde_json::to_string(value)?);
+    } else {
+        println!("{}", serde_json::to_string_pretty(value)?);
+    }
+    Ok(())
+}
+
+pub fn print_yaml<T: ?Sized + serde::Serialize>(value: &T) -> Result {
+    print!("{}", serde_yaml::to_string(value)?);
     Ok(())
 }
 
@@ -240,6 +508,15 @@ pub fn status_json(status: &Option<PendingTxnStatus>) -> serde_json::Value {
     status.as_ref().map_or(json!(null), |s| json!(s.hash))
 }
 
+pub fn status_explorer_json(
+    status: &Option<PendingTxnStatus>,
+    network: Network,
+) -> serde_json::Value {
+    status
+        .as_ref()
+        .map_or(json!(null), |s| json!(explorer_url(network, &s.hash)))
+}
+
 pub async fn maybe_submit_txn(
     commit: bool,
     client: &Client,
@@ -260,3 +537,75 @@ pub async fn submit_txn(client: &Client, txn: &BlockchainTxn) -> Result<PendingT
         .await
         .map_err(|e| e.into())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<OsString> {
+        args.iter().map(OsString::from).collect()
+    }
+
+    fn merged(config: &str, given: &[&str]) -> Result<String> {
+        let config: toml::value::Table = toml::from_str(config).unwrap();
+        let merged = merge_config_args(args(given), &config)?;
+        let merged: Vec<String> = merged
+            .into_iter()
+            .map(|arg| arg.into_string().unwrap())
+            .collect();
+        Ok(merged.join(" "))
+    }
+
+    const CONFIG: &str = r#"
+        api-url = "http://localhost"
+        file = "a.key"
+
+        [oracle.report]
+        price = "coingecko"
+        block = "auto"
+        retry-attempts = 3
+        commit = true
+        force = false
+        "#;
+
+    #[test]
+    fn merge_config() {
+        let merged = merged(
+            CONFIG,
+            &["helium-wallet", "oracle", "report", "--block", "100"],
+        )
+        .unwrap();
+        assert!(merged.starts_with("helium-wallet --api-url http://localhost --file a.key"));
+        assert!(merged.contains("oracle report --block 100"));
+        for expected in ["--price coingecko", "--retry-attempts 3", "--commit"] {
+            assert!(merged.contains(expected));
+        }
+        assert!(!merged.contains("auto"));
+        assert!(!merged.contains("--force"));
+    }
+
+    #[test]
+    fn merge_config_short_alias() {
+        let merged = merged(
+            CONFIG,
+            &["helium-wallet", "-f", "b.key", "oracle", "report"],
+        )
+        .unwrap();
+        assert!(merged.contains("-f b.key"));
+        assert!(!merged.contains("a.key"));
+    }
+
+    #[test]
+    fn merge_config_other_subcommand() {
+        let merged = merged(CONFIG, &["helium-wallet", "oracle", "check", "kraken"]).unwrap();
+        assert_eq!(
+            merged,
+            "helium-wallet --api-url http://localhost --file a.key oracle check kraken"
+        );
+        assert!(merged(
+            "price = \"coingecko\"",
+            &["helium-wallet", "oracle", "report"]
+        )
+        .is_err());
+    }
+}
@@ -1,18 +1,34 @@
 use crate::{
     cmd::*,
-    result::{anyhow, Result},
+    keypair::{Keypair, Network, PublicKey},
+    result::{anyhow, bail, Error, Result},
     traits::{TxnEnvelope, TxnSign, B64},
+    wallet::Wallet,
 };
-use helium_api::blocks;
+use helium_api::{blocks, oracle, transactions};
+use prettytable::{format, Table};
+use rand::Rng;
 use rust_decimal::{prelude::*, Decimal};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
-use std::str::FromStr;
+use std::{
+    fmt,
+    io::Write,
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 /// Report an oracle price to the blockchain
 #[derive(Debug, StructOpt)]
 pub enum Cmd {
     Report(Report),
+    Submit(Submit),
+    Current(Current),
+    History(History),
+    Decode(Decode),
+    Check(Check),
+    CostEstimate(CostEstimate),
+    Quote(Quote),
 }
 
 #[derive(Debug, StructOpt)]
@@ -20,77 +36,1555 @@ pub enum Cmd {
 /// Helium Blockchain.
 pub struct Report {
     /// The oracle price to report. Specify in USD or supply one of the
-    /// supported price lookup services ("coingecko", "bilaxy", "binance").
+    /// supported price lookup services ("coingecko", "bilaxy", "binance",
+    /// "kraken", "coinbase", "cmc", "gateio", "kucoin", "okx", "bitfinex",
+    /// "gemini", "huobi"). A custom
+    /// source can be given as "<url>=<path>", where path locates the price in
+    /// the JSON response, for example "data[0].last". Use "file:<path>" to
+    /// read the price from a file.
+    #[structopt(long)]
+    price: Price,
+
+    /// Price source to fall back to when fetching from --price fails. May be
+    /// repeated; fallbacks are tried in the order given and the first price
+    /// found is reported.
+    #[structopt(long, number_of_values = 1)]
+    fallback_price: Vec<Price>,
+
+    /// Price source to skip for this run when given as --price or
+    /// --fallback-price, for example from a --config file. May be repeated.
+    #[structopt(long, number_of_values = 1)]
+    disable: Vec<Price>,
+
+    #[structopt(flatten)]
+    fetch: FetchOpts,
+
+    /// How to round the price to the decimal places reported on chain
+    /// ("half-up", "half-even" or "down")
+    #[structopt(long, default_value = "half-up")]
+    rounding: Rounding,
+
+    /// Number of decimal places the price is scaled to. The chain expects
+    /// 8, so other values can not be combined with --commit.
+    #[structopt(long, default_value = "8")]
+    decimals: u32,
+
+    /// Block height to report the price at. Use "auto" to pick the
+    /// latest known block height from the API.
+    #[structopt(long)]
+    block: Block,
+
+    /// Number of blocks to add to the given or "auto" block height. May be
+    /// negative.
+    #[structopt(long, default_value = "0", allow_hyphen_values = true)]
+    block_offset: i64,
+
+    /// Maximum number of blocks a given block height may be behind or ahead
+    /// of the current chain height
+    #[structopt(long, default_value = "50")]
+    max_block_distance: u64,
+
+    /// Refuse to commit a price that deviates from the current oracle price
+    /// by more than the given percentage.
+    #[structopt(long)]
+    max_deviation: Option<Decimal>,
+
+    /// Skip committing a price within the given percentage of the price
+    /// recorded in the --state-file
+    #[structopt(long, requires = "state-file")]
+    deadband: Option<Decimal>,
+
+    /// Commit even when the price or block height fails a safety check
+    #[structopt(long)]
+    force: bool,
+
+    /// Print the price and block height that would be reported without
+    /// loading the wallet or constructing a transaction. The mainnet API is
+    /// used unless --testnet or --api-url is given.
+    #[structopt(long, conflicts_with = "commit")]
+    dry_run: bool,
+
+    /// Construct the report with an empty signature without decrypting the
+    /// wallet, for testing. The unsigned transaction is included in json
+    /// output and written by --out.
+    #[structopt(long, conflicts_with = "commit")]
+    no_sign: bool,
+
+    /// Append a CSV row for every committed report to the given file
+    #[structopt(long)]
+    log_file: Option<PathBuf>,
+
+    /// Append every committed report as a line of json to the given file
+    #[structopt(long)]
+    ndjson_file: Option<PathBuf>,
+
+    /// File to record the last committed report in. A report for the same
+    /// block height as the recorded one is refused.
+    #[structopt(long)]
+    state_file: Option<PathBuf>,
+
+    /// Read the wallet password from the first line of the given file. Takes
+    /// precedence over HELIUM_WALLET_PASSWORD and the interactive prompt.
+    #[structopt(long)]
+    password_file: Option<PathBuf>,
+
+    /// Refuse to report unless the wallet's address is the given one
+    #[structopt(long)]
+    expect_address: Option<PublicKey>,
+
+    /// Write the base64 encoded signed transaction to the given file
+    #[structopt(long)]
+    out: Option<PathBuf>,
+
+    /// Commit the oracle price report to the API
+    #[structopt(long)]
+    commit: bool,
+
+    /// After committing, wait for the report to appear on chain
+    #[structopt(long, requires = "commit")]
+    wait: bool,
+
+    /// Number of seconds to wait for a committed report to clear
+    #[structopt(long, default_value = "60")]
+    timeout: u64,
+}
+
+#[derive(Debug, StructOpt)]
+/// Submit a signed oracle price report read from a file, for example one
+/// written by "report --out". No wallet is needed since the report is
+/// already signed.
+pub struct Submit {
+    /// File to read the base64 encoded signed report from
+    #[structopt(long = "in")]
+    input: PathBuf,
+}
+
+#[derive(Debug, StructOpt)]
+/// Display the current oracle price on the blockchain. No wallet is needed.
+pub struct Current {
+    /// The network to get the oracle price for (mainnet/testnet)
+    #[structopt(long, default_value = "mainnet")]
+    network: Network,
+}
+
+#[derive(Debug, StructOpt)]
+/// List recent oracle price reports made by the wallet's public key. The
+/// wallet is not decrypted.
+pub struct History {
+    /// Maximum number of reports to list
+    #[structopt(long, default_value = "10")]
+    limit: usize,
+
+    /// Only list reports at or after the given block height
+    #[structopt(long)]
+    since: Option<u64>,
+
+    /// Only list reports at or before the given block height
+    #[structopt(long)]
+    until: Option<u64>,
+}
+
+#[derive(Debug, StructOpt)]
+/// Decode an oracle price as reported on chain into its decimal value. No
+/// wallet is needed.
+pub struct Decode {
+    /// The on chain price, in dollars scaled by 10^decimals
+    #[structopt(long)]
+    millis: u64,
+
+    /// Number of decimal places the price is scaled to. The chain expects
+    /// 8, so other values can not be combined with --commit.
+    #[structopt(long, default_value = "8")]
+    decimals: u32,
+}
+
+#[derive(Debug, StructOpt)]
+/// Fetch the price from each of the given price sources once and report
+/// its price and latency. Fails if any source fails. No wallet is needed.
+pub struct Check {
+    /// The price sources to check, as given to "report --price"
+    #[structopt(required = true)]
+    sources: Vec<Price>,
+
+    #[structopt(flatten)]
+    fetch: FetchOpts,
+}
+
+#[derive(Debug, StructOpt)]
+/// Estimate how many price reports a given reporting cadence makes per day
+/// and per month. Oracle price reports carry no transaction fee.
+pub struct CostEstimate {
+    /// Average number of minutes between reports
+    #[structopt(long)]
+    delay: Decimal,
+}
+
+#[derive(Debug, StructOpt)]
+/// Fetch and print the price that would be reported, rounded as it would be
+/// reported on chain. No wallet is needed.
+pub struct Quote {
+    /// The price source to quote, as given to "report --price"
     #[structopt(long)]
     price: Price,
 
-    /// Block height to report the price at. Use "auto" to pick the
-    /// latest known block height from the API.
-    #[structopt(long)]
-    block: Block,
+    #[structopt(flatten)]
+    fetch: FetchOpts,
+
+    /// Also copy the price to the clipboard. On Linux the command keeps
+    /// running after printing the price, to serve the clipboard, until
+    /// something else is copied.
+    #[cfg(feature = "clipboard")]
+    #[structopt(long)]
+    clipboard: bool,
+}
+
+/// Options controlling how prices are fetched from price lookup services
+#[derive(Debug, StructOpt)]
+pub struct FetchOpts {
+    /// Currency to quote the price in. Only "coingecko" supports currencies
+    /// other than "usd".
+    #[structopt(long, default_value = "usd")]
+    currency: String,
+
+    /// Symbol of the asset to look up the price of
+    #[structopt(long, default_value = "HNT")]
+    symbol: String,
+
+    /// Coin id to look the asset up by on CoinGecko, for example "bitcoin".
+    /// Defaults to the id of well known symbols, and to the lowercase symbol
+    /// otherwise.
+    #[structopt(long)]
+    coingecko_id: Option<String>,
+
+    /// API key for the CoinMarketCap ("cmc") price source. Defaults to the
+    /// CMC_API_KEY environment variable.
+    #[structopt(long)]
+    cmc_api_key: Option<String>,
+
+    /// USD value of one USDT, applied to prices from sources that quote
+    /// against USDT ("binance-int", "gateio", "kucoin", "okx", "huobi")
+    #[structopt(long, default_value = "1.0")]
+    usdt_usd: Decimal,
+
+    /// Host to use for the "binance-int" price source
+    #[structopt(long, default_value = "api.binance.com")]
+    binance_int_host: String,
+
+    /// Timeout in seconds for connecting to and reading from a price source
+    #[structopt(long, default_value = "10")]
+    fetch_timeout_secs: u64,
+
+    /// Number of attempts to make when fetching from a price source
+    #[structopt(long, default_value = "1")]
+    retry_attempts: u32,
+
+    /// Delay in milliseconds between fetch attempts
+    #[structopt(long, default_value = "1000")]
+    retry_interval_ms: u64,
+
+    /// Double the delay between fetch attempts after every failure, up to a
+    /// minute
+    #[structopt(long)]
+    retry_backoff: bool,
+
+    /// Proxy to send price source requests through. Defaults to the proxy
+    /// given by the HTTP_PROXY or HTTPS_PROXY environment variables.
+    #[structopt(long)]
+    proxy: Option<String>,
+
+    /// Refuse a price whose quote time is more than the given number of
+    /// seconds old. Only applies to sources that report a quote time
+    /// ("binance-int", "huobi").
+    #[structopt(long)]
+    max_staleness_secs: Option<u64>,
+
+    /// Limit requests to a price source to the given number per minute, as
+    /// in "coingecko=30". May be repeated for different sources.
+    #[structopt(long, number_of_values = 1)]
+    rate_limit: Vec<RateLimit>,
+
+    /// Number of times to fetch the price when reporting. The median of
+    /// the fetched prices is reported.
+    #[structopt(long, default_value = "1")]
+    sample_count: u32,
+
+    /// Number of seconds to spread the --sample-count fetches over
+    #[structopt(long, default_value = "0")]
+    sample_window: u64,
+}
+
+/// A per minute request limit for a named price source
+#[derive(Debug)]
+struct RateLimit {
+    source: &'static str,
+    per_min: u32,
+}
+
+impl FromStr for RateLimit {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (source, per_min) = s
+            .split_once('=')
+            .ok_or_else(|| anyhow!("Rate limit must be <source>=<requests per minute>"))?;
+        let per_min: u32 = per_min.parse()?;
+        if per_min == 0 {
+            bail!("Rate limit for {} must be at least one request", source);
+        }
+        Ok(Self {
+            source: source.parse::<Price>()?.name(),
+            per_min,
+        })
+    }
+}
+
+/// The window a rate limit counts requests over
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60);
+
+lazy_static! {
+    /// Times of the latest requests to each rate limited price source, at
+    /// most its limit of them, shared by all fetches in the process
+    static ref RATE_LIMIT_SLOTS: std::sync::Mutex<
+        std::collections::HashMap<&'static str, std::collections::VecDeque<tokio::time::Instant>>,
+    > = Default::default();
+}
+
+impl RateLimit {
+    /// Waits until a request to the source is within its limit, so that no
+    /// minute ever sees more than the limit of requests. Each call reserves
+    /// its slot even if it has to wait for it, so concurrent fetches queue up
+    /// rather than all going at once.
+    async fn acquire(&self) {
+        let wait = {
+            let mut slots = RATE_LIMIT_SLOTS.lock().unwrap();
+            let slots = slots.entry(self.source).or_default();
+            let now = tokio::time::Instant::now();
+            let slot = if slots.len() < self.per_min as usize {
+                now
+            } else {
+                let oldest = slots.pop_front().unwrap_or(now);
+                (oldest + RATE_LIMIT_WINDOW).max(now)
+            };
+            slots.push_back(slot);
+            slot - now
+        };
+        tokio::time::sleep(wait).await;
+    }
+}
+
+/// Some price sources refuse requests without a user agent
+const USER_AGENT: &str = concat!("helium-wallet-oracle/", env!("CARGO_PKG_VERSION"));
+
+impl Default for FetchOpts {
+    fn default() -> Self {
+        Self::from_iter_safe(&["oracle"]).expect("default fetch options")
+    }
+}
+
+/// Setters for the fetch options, for use as a library
+impl FetchOpts {
+    pub fn currency(mut self, currency: &str) -> Self {
+        self.currency = currency.to_string();
+        self
+    }
+
+    pub fn symbol(mut self, symbol: &str) -> Self {
+        self.symbol = symbol.to_string();
+        self
+    }
+
+    pub fn coingecko_id(mut self, id: &str) -> Self {
+        self.coingecko_id = Some(id.to_string());
+        self
+    }
+
+    pub fn cmc_api_key(mut self, key: &str) -> Self {
+        self.cmc_api_key = Some(key.to_string());
+        self
+    }
+
+    pub fn usdt_usd(mut self, usdt_usd: Decimal) -> Self {
+        self.usdt_usd = usdt_usd;
+        self
+    }
+
+    pub fn binance_int_host(mut self, host: &str) -> Self {
+        self.binance_int_host = host.to_string();
+        self
+    }
+
+    pub fn fetch_timeout_secs(mut self, secs: u64) -> Self {
+        self.fetch_timeout_secs = secs;
+        self
+    }
+
+    pub fn retry_attempts(mut self, attempts: u32) -> Self {
+        self.retry_attempts = attempts;
+        self
+    }
+
+    pub fn retry_interval_ms(mut self, ms: u64) -> Self {
+        self.retry_interval_ms = ms;
+        self
+    }
+
+    pub fn retry_backoff(mut self, backoff: bool) -> Self {
+        self.retry_backoff = backoff;
+        self
+    }
+
+    pub fn proxy(mut self, proxy: &str) -> Self {
+        self.proxy = Some(proxy.to_string());
+        self
+    }
+
+    pub fn max_staleness_secs(mut self, secs: u64) -> Self {
+        self.max_staleness_secs = Some(secs);
+        self
+    }
+
+    /// Limits requests to the named price source to the given number per
+    /// minute. Fails for an unknown source or a limit of zero.
+    pub fn rate_limit(mut self, source: &str, per_min: u32) -> Result<Self> {
+        self.rate_limit
+            .push(format!("{}={}", source, per_min).parse()?);
+        Ok(self)
+    }
+}
+
+impl FetchOpts {
+    fn is_usd(&self) -> bool {
+        self.currency.eq_ignore_ascii_case("usd")
+    }
+
+    fn client(&self) -> Result<reqwest::Client> {
+        let timeout = Duration::from_secs(self.fetch_timeout_secs);
+        let mut builder = reqwest::Client::builder()
+            .user_agent(USER_AGENT)
+            .connect_timeout(timeout)
+            .timeout(timeout);
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+        }
+        Ok(builder.build()?)
+    }
+
+    fn asset_symbol(&self) -> String {
+        self.symbol.to_uppercase()
+    }
+
+    /// The coin id CoinGecko knows the symbol by
+    fn coingecko_coin_id(&self) -> String {
+        if let Some(id) = &self.coingecko_id {
+            return id.clone();
+        }
+        let id = match self.asset_symbol().as_str() {
+            "HNT" => "helium",
+            "IOT" => "helium-iot",
+            "MOBILE" => "helium-mobile",
+            "BTC" => "bitcoin",
+            "ETH" => "ethereum",
+            "SOL" => "solana",
+            "USDT" => "tether",
+            "USDC" => "usd-coin",
+            _ => return self.symbol.to_lowercase(),
+        };
+        id.to_string()
+    }
+
+    fn cmc_key(&self) -> Result<String> {
+        self.cmc_api_key
+            .clone()
+            .or_else(|| std::env::var("CMC_API_KEY").ok())
+            .ok_or_else(|| anyhow!("CoinMarketCap requires --cmc-api-key or CMC_API_KEY"))
+    }
+}
+
+/// Fetches the price from the given price source, named as it would be
+/// given to "report --price". Fetch options other than the defaults are set
+/// on FetchOpts::default(), as in FetchOpts::default().retry_attempts(3).
+pub async fn fetch_price(source: &str, opts: &FetchOpts) -> Result<Usd> {
+    source.parse::<Price>()?.fetch(opts, false).await
+}
+
+impl Cmd {
+    pub async fn run(&self, opts: Opts) -> Result {
+        match self {
+            Cmd::Report(cmd) => cmd.run(opts).await,
+            Cmd::Submit(cmd) => cmd.run(opts).await,
+            Cmd::Current(cmd) => cmd.run(opts).await,
+            Cmd::History(cmd) => cmd.run(opts).await,
+            Cmd::Decode(cmd) => cmd.run(opts).await,
+            Cmd::Check(cmd) => cmd.run(opts).await,
+            Cmd::CostEstimate(cmd) => cmd.run(opts).await,
+            Cmd::Quote(cmd) => cmd.run(opts).await,
+        }
+    }
+}
+
+/// The blockchain API calls made in reporting an oracle price
+trait OracleApi {
+    async fn height(&self) -> Result<u64>;
+    async fn current_price(&self) -> Result<Usd>;
+    async fn submit(&self, envelope: &BlockchainTxn) -> Result<PendingTxnStatus>;
+    async fn is_cleared(&self, hash: &str) -> bool;
+}
+
+impl OracleApi for Client {
+    async fn height(&self) -> Result<u64> {
+        Ok(blocks::height(self).await?)
+    }
+
+    async fn current_price(&self) -> Result<Usd> {
+        Ok(oracle::prices::current(self).await?.price)
+    }
+
+    async fn submit(&self, envelope: &BlockchainTxn) -> Result<PendingTxnStatus> {
+        submit_txn(self, envelope)
+            .await
+            .map_err(|err| err.context(OracleError::SubmitFailed))
+    }
+
+    async fn is_cleared(&self, hash: &str) -> bool {
+        transactions::get(self, hash).await.is_ok()
+    }
+}
+
+/// API clients that are tried in order until one succeeds
+struct Failover(Vec<Client>);
+
+impl Failover {
+    fn new(urls: Vec<String>) -> Self {
+        Self(urls.into_iter().map(new_client).collect())
+    }
+
+    /// Makes the given call against each API in turn, returning the first
+    /// success or else the last failure
+    async fn first_ok<T>(&self, call: impl AsyncFn(&Client) -> Result<T>) -> Result<T> {
+        let mut last_err = anyhow!("No API URL given");
+        for client in &self.0 {
+            match call(client).await {
+                Ok(value) => return Ok(value),
+                Err(err) => last_err = err,
+            }
+        }
+        Err(last_err)
+    }
+}
+
+impl OracleApi for Failover {
+    async fn height(&self) -> Result<u64> {
+        self.first_ok(async |client| client.height().await).await
+    }
+
+    async fn current_price(&self) -> Result<Usd> {
+        self.first_ok(async |client| client.current_price().await)
+            .await
+    }
+
+    async fn submit(&self, envelope: &BlockchainTxn) -> Result<PendingTxnStatus> {
+        self.first_ok(async |client| client.submit(envelope).await)
+            .await
+    }
+
+    async fn is_cleared(&self, hash: &str) -> bool {
+        for client in &self.0 {
+            if client.is_cleared(hash).await {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+impl Report {
+    pub async fn run(&self, opts: Opts) -> Result {
+        let wallet = if self.dry_run {
+            None
+        } else {
+            Some(load_wallet(opts.files.clone())?)
+        };
+        let wallet_network = wallet
+            .as_ref()
+            .map_or(Network::MainNet, |wallet| wallet.public_key.network);
+        let network = opts.network(wallet_network);
+        let api = Failover::new(opts.api_urls(wallet_network));
+        self.report(&api, wallet.as_ref(), network, opts.format, opts.verbose)
+            .await
+    }
+
+    /// Makes the report for the given wallet, which is only needed when not
+    /// a --dry-run
+    async fn report(
+        &self,
+        api: &impl OracleApi,
+        wallet: Option<&Wallet>,
+        network: Network,
+        format: OutputFormat,
+        verbose: bool,
+    ) -> Result {
+        check_decimals(self.decimals)?;
+        if self.commit && self.decimals != PRICE_DECIMALS {
+            bail!(
+                "Can not commit a price scaled to {} decimals, the chain expects {}",
+                self.decimals,
+                PRICE_DECIMALS
+            );
+        }
+        if let (Some(expected), Some(wallet)) = (&self.expect_address, wallet) {
+            if &wallet.public_key != expected {
+                bail!(
+                    "Loaded key {} does not match --expect-address {}",
+                    wallet.public_key,
+                    expected
+                );
+            }
+        }
+        if let (Block::Height(height), false) = (self.block, self.force) {
+            let height = self.offset_block(height)?;
+            check_block_height(api, height, self.max_block_distance).await?;
+            self.check_state(height)?;
+        }
+        let (source, usd) = self.fetch_price(verbose).await?;
+        let price = to_oracle_price_with(usd, self.rounding, self.decimals)?;
+        check_price(price)?;
+        let current = api.current_price().await.ok();
+        if self.commit && !self.force {
+            if let Some(max_deviation) = self.max_deviation {
+                check_deviation(current, usd, max_deviation)?;
+            }
+        }
+        if self.within_deadband(price) {
+            print_info(format_args!(
+                "Price {} is within the deadband, skipping",
+                usd
+            ));
+            return Ok(());
+        }
+        if self.dry_run {
+            let block_height = self.offset_block(self.block.to_block(api).await?)?;
+            return print_dry_run(source, current, price, block_height, format, self.decimals);
+        }
+        let wallet =
+            wallet.ok_or_else(|| anyhow!("A wallet is needed unless --dry-run is given"))?;
+        if let (OutputFormat::Table, Some(current)) = (&format, current) {
+            println!(
+                "Current: {}, Reporting: {} ({})",
+                current,
+                usd,
+                format_change(percent_change(current, usd))
+            );
+        }
+
+        let txn = if self.no_sign {
+            self.unsigned(api, &wallet.public_key, price).await?
+        } else {
+            let password = match &self.password_file {
+                Some(path) => read_password_file(path)?,
+                None => get_password(false)?,
+            };
+            let keypair = wallet.decrypt(password.as_bytes())?;
+            self.sign(api, &keypair, price).await?
+        };
+
+        let envelope = txn.in_envelope();
+        if let Some(path) = &self.out {
+            let mut file = open_output_file(path, false)?;
+            writeln!(file, "{}", envelope.to_b64()?)?;
+        }
+        let status = if self.commit {
+            Some(api.submit(&envelope).await?)
+        } else {
+            None
+        };
+        if let (Some(path), Some(_)) = (&self.log_file, &status) {
+            append_log(path, source, &txn, &status, self.decimals)?;
+        }
+        if let (Some(path), Some(_)) = (&self.ndjson_file, &status) {
+            let report = txn_json(
+                source.name(),
+                current,
+                &txn,
+                &status,
+                network,
+                self.decimals,
+            )?;
+            append_ndjson(path, &report)?;
+        }
+        if let (Some(path), Some(_)) = (&self.state_file, &status) {
+            ReportState::from(&txn).save(path)?;
+        }
+        print_txn(
+            source.name(),
+            current,
+            &txn,
+            &status,
+            network,
+            format,
+            self.decimals,
+        )?;
+        match (&status, self.wait) {
+            (Some(status), true) => {
+                wait_for_txn(api, &status.hash, Duration::from_secs(self.timeout)).await
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Fetches the price from --price, falling back to each --fallback-price
+    /// in turn and skipping any --disable'd source. Returns the source the
+    /// price was found at.
+    async fn fetch_price(&self, verbose: bool) -> Result<(&Price, Usd)> {
+        let mut sources = std::iter::once(&self.price)
+            .chain(&self.fallback_price)
+            .filter(|source| !self.is_disabled(source));
+        let mut source = sources.next().ok_or(OracleError::NoSources)?;
+        loop {
+            match (source.sample(&self.fetch, verbose).await, sources.next()) {
+                (Ok(usd), _) => return Ok((source, usd)),
+                (Err(err), None) => return Err(err),
+                (Err(err), Some(fallback)) => {
+                    print_info(format_args!(
+                        "{} failed, falling back to {}: {}",
+                        source, fallback, err
+                    ));
+                    source = fallback;
+                }
+            }
+        }
+    }
+
+    fn is_disabled(&self, source: &Price) -> bool {
+        self.disable
+            .iter()
+            .any(|disabled| disabled.to_string() == source.to_string())
+    }
+
+    /// Constructs and signs the report for the given oracle price
+    async fn sign(
+        &self,
+        api: &impl OracleApi,
+        keypair: &Keypair,
+        price: u64,
+    ) -> Result<BlockchainTxnPriceOracleV1> {
+        let mut txn = self.unsigned(api, keypair.public_key(), price).await?;
+        txn.signature = txn.sign(keypair)?;
+        Ok(txn)
+    }
+
+    /// Constructs the report for the given oracle price with an empty
+    /// signature
+    async fn unsigned(
+        &self,
+        api: &impl OracleApi,
+        public_key: &PublicKey,
+        price: u64,
+    ) -> Result<BlockchainTxnPriceOracleV1> {
+        // Resolve an "auto" block height only now so it is not made stale
+        // by the time spent fetching the price and entering the password
+        let block_height = self.offset_block(self.block.to_block(api).await?)?;
+        if let (Block::Auto, false) = (self.block, self.force) {
+            self.check_state(block_height)?;
+        }
+        Ok(BlockchainTxnPriceOracleV1 {
+            public_key: public_key.into(),
+            price,
+            block_height,
+            signature: Vec::new(),
+        })
+    }
+
+    /// Applies the --block-offset to a resolved block height
+    fn offset_block(&self, height: u64) -> Result<u64> {
+        i64::try_from(height)
+            .ok()
+            .and_then(|height| height.checked_add(self.block_offset))
+            .filter(|height| *height > 0)
+            .map(|height| height as u64)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Block {} with offset {} is not a valid block height",
+                    height,
+                    self.block_offset
+                )
+            })
+    }
+
+    /// Whether a committed report of the given price would move less than
+    /// the --deadband from the price recorded in the --state-file
+    fn within_deadband(&self, price: u64) -> bool {
+        let (Some(deadband), true, false) = (self.deadband, self.commit, self.force) else {
+            return false;
+        };
+        match self.state_file.as_deref().and_then(ReportState::load) {
+            Some(state) => percent_change(
+                from_oracle_price_with(state.price, self.decimals),
+                from_oracle_price_with(price, self.decimals),
+            )
+            .is_some_and(|change| change.abs() < deadband),
+            None => false,
+        }
+    }
+
+    /// Refuses to commit a report for the block height recorded in the
+    /// --state-file
+    fn check_state(&self, block_height: u64) -> Result {
+        match self.state_file.as_deref().and_then(ReportState::load) {
+            Some(state) if self.commit && state.block_height == block_height => bail!(
+                "A price of {} was already reported for block {}. Use --force to commit anyway",
+                from_oracle_price_with(state.price, self.decimals),
+                block_height
+            ),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Submit {
+    pub async fn run(&self, opts: Opts) -> Result {
+        let encoded = fs::read_to_string(&self.input)?;
+        let envelope = BlockchainTxn::from_b64(encoded.trim())?;
+        let txn = BlockchainTxnPriceOracleV1::from_envelope(&envelope)
+            .map_err(|_| anyhow!("{} is not an oracle price report", self.input.display()))?;
+        let public_key = PublicKey::from_bytes(&txn.public_key)?;
+        txn.verify(&public_key, &txn.signature)?;
+        check_price(txn.price)?;
+
+        let api = Failover::new(opts.api_urls(public_key.network));
+        let status = Some(api.submit(&envelope).await?);
+        print_txn(
+            "unknown",
+            None,
+            &txn,
+            &status,
+            opts.network(public_key.network),
+            opts.format,
+            PRICE_DECIMALS,
+        )
+    }
+}
+
+impl Current {
+    pub async fn run(&self, opts: Opts) -> Result {
+        let client = new_client(opts.api_url(self.network));
+        let current = oracle::prices::current(&client).await?;
+        let table = json!({
+            "schema_version": JSON_SCHEMA_VERSION,
+            "version": env!("CARGO_PKG_VERSION"),
+            "price": to_oracle_price(current.price)?,
+            "block_height": current.block,
+        });
+        match opts.format {
+            OutputFormat::Table => {
+                ptable!(
+                    ["Key", "Value"],
+                    ["Block Height", current.block],
+                    ["Price", current.price]
+                );
+                Ok(())
+            }
+            OutputFormat::Json => print_json(&table),
+            OutputFormat::Yaml => print_yaml(&table),
+            OutputFormat::Csv => {
+                println!("block_height,price");
+                println!("{},{}", current.block, current.price);
+                Ok(())
+            }
+        }
+    }
+}
+
+impl History {
+    pub async fn run(&self, opts: Opts) -> Result {
+        let wallet = load_wallet(opts.files.clone())?;
+        let base_url = opts.api_url(wallet.public_key.network);
+        let reports = fetch_history(
+            &base_url,
+            &wallet.public_key,
+            self.limit,
+            self.since,
+            self.until,
+        )
+        .await?;
+        let table = json!({
+            "schema_version": JSON_SCHEMA_VERSION,
+            "version": env!("CARGO_PKG_VERSION"),
+            "reports": reports,
+        });
+        match opts.format {
+            OutputFormat::Table => {
+                let mut table = Table::new();
+                table.set_format(*format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
+                table.set_titles(row!["Block Height", "Price", "Hash"]);
+                for report in &reports {
+                    table.add_row(row![
+                        report.block_height,
+                        from_oracle_price(report.price),
+                        report.hash
+                    ]);
+                }
+                print_table(&table)
+            }
+            OutputFormat::Json => print_json(&table),
+            OutputFormat::Yaml => print_yaml(&table),
+            OutputFormat::Csv => {
+                println!("block_height,price,hash");
+                for report in &reports {
+                    println!(
+                        "{},{},{}",
+                        report.block_height,
+                        from_oracle_price(report.price),
+                        report.hash
+                    );
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Decode {
+    pub async fn run(&self, opts: Opts) -> Result {
+        check_decimals(self.decimals)?;
+        let price = from_oracle_price_with(self.millis, self.decimals);
+        let table = json!({
+            "schema_version": JSON_SCHEMA_VERSION,
+            "version": env!("CARGO_PKG_VERSION"),
+            "millis": self.millis,
+            "price": price.get_decimal().normalize().to_string(),
+        });
+        match opts.format {
+            OutputFormat::Table => {
+                println!("{}", price.get_decimal().normalize());
+                Ok(())
+            }
+            OutputFormat::Json => print_json(&table),
+            OutputFormat::Yaml => print_yaml(&table),
+            OutputFormat::Csv => {
+                println!("millis,price");
+                println!("{},{}", self.millis, price.get_decimal().normalize());
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Quotes a csv field that contains a comma, quote or line break, doubling
+/// any quotes in it
+fn csv_field(field: &str) -> String {
+    if field.contains(|c: char| matches!(c, ',' | '"' | '\n' | '\r')) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// The outcome of fetching from one price source in "check"
+#[derive(Debug, Serialize)]
+struct SourceCheck {
+    source: String,
+    price: Option<String>,
+    latency_ms: u128,
+    error: Option<String>,
+}
+
+impl Check {
+    pub async fn run(&self, opts: Opts) -> Result {
+        let mut checks = Vec::with_capacity(self.sources.len());
+        for source in &self.sources {
+            let start = tokio::time::Instant::now();
+            let result = source.fetch(&self.fetch, opts.verbose).await;
+            checks.push(SourceCheck {
+                source: source.to_string(),
+                price: result
+                    .as_ref()
+                    .ok()
+                    .map(|usd| usd.get_decimal().to_string()),
+                latency_ms: start.elapsed().as_millis(),
+                error: result.err().map(|err| err.to_string()),
+            });
+        }
+        let table = json!({
+            "schema_version": JSON_SCHEMA_VERSION,
+            "version": env!("CARGO_PKG_VERSION"),
+            "sources": checks,
+        });
+        match opts.format {
+            OutputFormat::Table => {
+                let mut table = Table::new();
+                table.set_format(*format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
+                table.set_titles(row!["Source", "Price", "Latency (ms)", "Error"]);
+                for check in &checks {
+                    table.add_row(row![
+                        check.source,
+                        check.price.as_deref().unwrap_or("-"),
+                        check.latency_ms,
+                        check.error.as_deref().unwrap_or("")
+                    ]);
+                }
+                print_table(&table)?;
+            }
+            OutputFormat::Json => print_json(&table)?,
+            OutputFormat::Yaml => print_yaml(&table)?,
+            OutputFormat::Csv => {
+                println!("source,price,latency_ms,error");
+                for check in &checks {
+                    println!(
+                        "{},{},{},{}",
+                        csv_field(&check.source),
+                        check.price.as_deref().unwrap_or(""),
+                        check.latency_ms,
+                        csv_field(check.error.as_deref().unwrap_or(""))
+                    );
+                }
+            }
+        }
+        let failed = checks.iter().filter(|check| check.error.is_some()).count();
+        if failed > 0 {
+            bail!("{} of {} price sources failed", failed, checks.len());
+        }
+        Ok(())
+    }
+}
+
+/// Minutes in a day, and days in the month used for estimates
+const MINUTES_PER_DAY: i64 = 24 * 60;
+const DAYS_PER_MONTH: i64 = 30;
+
+impl CostEstimate {
+    pub async fn run(&self, opts: Opts) -> Result {
+        if self.delay <= Decimal::ZERO {
+            bail!("Delay must be a positive number of minutes");
+        }
+        let per_day = Decimal::from(MINUTES_PER_DAY)
+            .checked_div(self.delay)
+            .ok_or_else(|| anyhow!("Delay {} is too small to estimate", self.delay))?
+            .round_dp(2);
+        let per_month = per_day
+            .checked_mul(Decimal::from(DAYS_PER_MONTH))
+            .ok_or_else(|| anyhow!("Delay {} is too small to estimate", self.delay))?
+            .round_dp(2);
+        let table = json!({
+            "schema_version": JSON_SCHEMA_VERSION,
+            "version": env!("CARGO_PKG_VERSION"),
+            "delay_minutes": self.delay.to_string(),
+            "reports_per_day": per_day.to_string(),
+            "reports_per_month": per_month.to_string(),
+            "fee_dc": 0,
+        });
+        match opts.format {
+            OutputFormat::Table => {
+                ptable!(
+                    ["Key", "Value"],
+                    ["Delay (minutes)", self.delay],
+                    ["Reports per Day", per_day],
+                    ["Reports per Month", per_month],
+                    ["Fee (DC)", 0]
+                );
+                Ok(())
+            }
+            OutputFormat::Json => print_json(&table),
+            OutputFormat::Yaml => print_yaml(&table),
+            OutputFormat::Csv => {
+                println!("delay_minutes,reports_per_day,reports_per_month,fee_dc");
+                println!("{},{},{},0", self.delay, per_day, per_month);
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Quote {
+    pub async fn run(&self, opts: Opts) -> Result {
+        let usd = self.price.sample(&self.fetch, opts.verbose).await?;
+        let price = from_oracle_price(to_oracle_price(usd)?)
+            .get_decimal()
+            .normalize();
+        let table = json!({
+            "schema_version": JSON_SCHEMA_VERSION,
+            "version": env!("CARGO_PKG_VERSION"),
+            "price": price.to_string(),
+            "source": self.price.name(),
+        });
+        match opts.format {
+            OutputFormat::Table => println!("{}", price),
+            OutputFormat::Json => print_json(&table)?,
+            OutputFormat::Yaml => print_yaml(&table)?,
+            OutputFormat::Csv => {
+                println!("price,source");
+                println!("{},{}", price, self.price.name());
+            }
+        }
+        #[cfg(feature = "clipboard")]
+        if self.clipboard {
+            copy_to_clipboard(price.to_string())?;
+        }
+        Ok(())
+    }
+}
+
+/// Copies the text to the clipboard. On Linux the clipboard contents are
+/// served by the process that set them and are lost when it exits, so this
+/// waits until something else takes over the clipboard.
+#[cfg(feature = "clipboard")]
+fn copy_to_clipboard(text: String) -> Result {
+    let mut clipboard = arboard::Clipboard::new()?;
+    #[cfg(target_os = "linux")]
+    {
+        use arboard::SetExtLinux;
+        print_info(format_args!(
+            "Keeping the price on the clipboard until something else is copied"
+        ));
+        clipboard.set().wait().text(text)?;
+    }
+    #[cfg(not(target_os = "linux"))]
+    clipboard.set_text(text)?;
+    Ok(())
+}
+
+/// A price report as listed in the oracle activity of the API
+#[derive(Debug, Serialize, Deserialize)]
+struct HistoryReport {
+    block_height: u64,
+    price: u64,
+    hash: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct HistoryPage {
+    data: Vec<serde_json::Value>,
+    cursor: Option<String>,
+}
+
+/// Fetches up to limit of the most recent price reports made by the given
+/// oracle key within the given block heights, following the API's paging
+/// cursor as needed.
+async fn fetch_history(
+    base_url: &str,
+    address: &PublicKey,
+    limit: usize,
+    since: Option<u64>,
+    until: Option<u64>,
+) -> Result<Vec<HistoryReport>> {
+    let client = reqwest::Client::new();
+    let url = format!(
+        "{}/oracle/{}/activity",
+        base_url.trim_end_matches('/'),
+        address
+    );
+    let mut reports = Vec::new();
+    let mut cursor: Option<String> = None;
+    while reports.len() < limit {
+        let mut request = client.get(&url);
+        if let Some(cursor) = &cursor {
+            request = request.query(&[("cursor", cursor)]);
+        }
+        let page: HistoryPage = request.send().await?.error_for_status()?.json().await?;
+        // Activity is listed newest first, so paging can stop at the first
+        // report before --since
+        let mut past_since = false;
+        for txn in page.data {
+            if txn["type"] != "price_oracle_v1" {
+                continue;
+            }
+            let report: HistoryReport = serde_json::from_value(txn)?;
+            if since.is_some_and(|since| report.block_height < since) {
+                past_since = true;
+                break;
+            }
+            if until.is_none_or(|until| report.block_height <= until) {
+                reports.push(report);
+            }
+        }
+        match page.cursor {
+            Some(next) if !past_since => cursor = Some(next),
+            _ => break,
+        }
+    }
+    reports.truncate(limit);
+    Ok(reports)
+}
+
+/// Version of the layout of the json objects printed by the oracle
+/// commands. Bump it when fields change.
+const JSON_SCHEMA_VERSION: u32 = 1;
+
+/// The json description of a report, as printed for --format json and
+/// written to the --ndjson-file
+fn txn_json(
+    source: &str,
+    current: Option<Usd>,
+    txn: &BlockchainTxnPriceOracleV1,
+    status: &Option<PendingTxnStatus>,
+    network: Network,
+    decimals: u32,
+) -> Result<serde_json::Value> {
+    let delta = current
+        .and_then(|current| percent_change(current, from_oracle_price_with(txn.price, decimals)));
+    Ok(json!({
+        "schema_version": JSON_SCHEMA_VERSION,
+        "version": env!("CARGO_PKG_VERSION"),
+        "price": txn.price,
+        "block_height": txn.block_height,
+        "source": source,
+        "current_price": current
+            .map(|current| to_oracle_price_with(current, Rounding::HalfUp, decimals))
+            .transpose()?,
+        "delta_pct": delta.map(|d| d.round_dp(2).to_string()),
+        "txn": txn.in_envelope().to_b64()?,
+        "hash": status_json(status),
+        "explorer_url": status_explorer_json(status, network)
+    }))
+}
+
+fn print_txn(
+    source: &str,
+    current: Option<Usd>,
+    txn: &BlockchainTxnPriceOracleV1,
+    status: &Option<PendingTxnStatus>,
+    network: Network,
+    format: OutputFormat,
+    decimals: u32,
+) -> Result {
+    let table = txn_json(source, current, txn, status, network, decimals)?;
+    let usd = from_oracle_price_with(txn.price, decimals);
+    let delta = current.and_then(|current| percent_change(current, usd));
+    match format {
+        OutputFormat::Table => {
+            ptable!(
+                ["Key", "Value"],
+                ["Block Height", txn.block_height],
+                ["Price", usd],
+                ["Source", source],
+                [
+                    "Current Price",
+                    current.map_or("unknown".to_string(), |c| c.to_string())
+                ],
+                ["Change", format_change(delta)],
+                ["Hash", status_str(status)]
+            );
+
+            print_footer(status, network)
+        }
+        OutputFormat::Json => print_json(&table),
+        OutputFormat::Yaml => print_yaml(&table),
+        OutputFormat::Csv => {
+            println!("block_height,price,hash");
+            println!("{},{},{}", txn.block_height, usd, status_str(status));
+            Ok(())
+        }
+    }
+}
+
+/// Delay between checks for a committed report in --wait
+const WAIT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Polls the API until the transaction with the given hash is on chain or
+/// the timeout elapses. The outcome is reported on stderr so it does not
+/// mix with json or csv output.
+async fn wait_for_txn(api: &impl OracleApi, hash: &str, timeout: Duration) -> Result {
+    let start = tokio::time::Instant::now();
+    loop {
+        if api.is_cleared(hash).await {
+            print_info(format_args!("Transaction {} cleared", hash));
+            return Ok(());
+        }
+        let elapsed = start.elapsed();
+        if elapsed >= timeout {
+            bail!(
+                "Transaction {} not found on chain after {}s",
+                hash,
+                elapsed.as_secs()
+            );
+        }
+        tokio::time::sleep(WAIT_INTERVAL.min(timeout - elapsed)).await;
+    }
+}
+
+/// The last committed report, as stored in the --state-file
+#[derive(Debug, Serialize, Deserialize)]
+struct ReportState {
+    block_height: u64,
+    price: u64,
+    timestamp: u64,
+}
+
+impl From<&BlockchainTxnPriceOracleV1> for ReportState {
+    fn from(txn: &BlockchainTxnPriceOracleV1) -> Self {
+        Self {
+            block_height: txn.block_height,
+            price: txn.price,
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+        }
+    }
+}
+
+impl ReportState {
+    /// Loads the state from the given file. A missing file is not an error,
+    /// and an unreadable one is reported and ignored.
+    fn load(path: &Path) -> Option<Self> {
+        if !path.exists() {
+            return None;
+        }
+        match Self::read(path) {
+            Ok(state) => Some(state),
+            Err(err) => {
+                print_info(format_args!(
+                    "Ignoring state file {}: {}",
+                    path.display(),
+                    err
+                ));
+                None
+            }
+        }
+    }
+
+    fn read(path: &Path) -> Result<Self> {
+        let file = fs::File::open(path)?;
+        Ok(serde_json::from_reader(file)?)
+    }
+
+    fn save(&self, path: &Path) -> Result {
+        let file = open_output_file(path, false)?;
+        serde_json::to_writer(file, self)?;
+        Ok(())
+    }
+}
+
+fn append_log(
+    path: &Path,
+    source: &Price,
+    txn: &BlockchainTxnPriceOracleV1,
+    status: &Option<PendingTxnStatus>,
+    decimals: u32,
+) -> Result {
+    let is_new = !path.exists();
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    if is_new {
+        writeln!(file, "timestamp,block_height,price,source,hash")?;
+    }
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    writeln!(
+        file,
+        "{},{},{},{},{}",
+        timestamp,
+        txn.block_height,
+        from_oracle_price_with(txn.price, decimals),
+        source.name(),
+        status_str(status)
+    )?;
+    file.flush()?;
+    Ok(())
+}
+
+/// Appends the given report as a single line of json to the given file
+fn append_ndjson(path: &Path, report: &serde_json::Value) -> Result {
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    writeln!(file, "{}", serde_json::to_string(report)?)?;
+    file.flush()?;
+    Ok(())
+}
+
+fn print_dry_run(
+    source: &Price,
+    current: Option<Usd>,
+    price: u64,
+    block_height: u64,
+    format: OutputFormat,
+    decimals: u32,
+) -> Result {
+    let usd = from_oracle_price_with(price, decimals);
+    let delta = current.and_then(|current| percent_change(current, usd));
+    let table = json!({
+        "schema_version": JSON_SCHEMA_VERSION,
+        "version": env!("CARGO_PKG_VERSION"),
+        "price": price,
+        "block_height": block_height,
+        "source": source.name(),
+        "current_price": current
+            .map(|current| to_oracle_price_with(current, Rounding::HalfUp, decimals))
+            .transpose()?,
+        "delta_pct": delta.map(|d| d.round_dp(2).to_string()),
+    });
+    match format {
+        OutputFormat::Table => {
+            ptable!(
+                ["Key", "Value"],
+                ["Block Height", block_height],
+                ["Price", usd],
+                ["Source", source.name()],
+                [
+                    "Current Price",
+                    current.map_or("unknown".to_string(), |c| c.to_string())
+                ],
+                ["Change", format_change(delta)]
+            );
+            Ok(())
+        }
+        OutputFormat::Json => print_json(&table),
+        OutputFormat::Yaml => print_yaml(&table),
+        OutputFormat::Csv => {
+            println!("block_height,price");
+            println!("{},{}", block_height, usd);
+            Ok(())
+        }
+    }
+}
+
+async fn check_block_height(api: &impl OracleApi, height: u64, max_distance: u64) -> Result {
+    let tip = api.height().await?;
+    if height.saturating_add(max_distance) < tip || height > tip.saturating_add(max_distance) {
+        return Err(OracleError::BlockOutOfRange { height, tip }.into());
+    }
+    Ok(())
+}
+
+fn check_deviation(current: Option<Usd>, usd: Usd, max_deviation: Decimal) -> Result {
+    let current = current.ok_or_else(|| {
+        anyhow!("Current oracle price is unavailable. Use --force to commit anyway")
+    })?;
+    if let Some(deviation) = percent_change(current, usd) {
+        if deviation.abs() > max_deviation {
+            return Err(OracleError::PriceDeviation {
+                price: usd,
+                current,
+                deviation: deviation.abs(),
+                max_deviation,
+            }
+            .into());
+        }
+    }
+    Ok(())
+}
+
+fn format_change(change: Option<Decimal>) -> String {
+    match change {
+        Some(change) if change.is_sign_negative() => format!("{}%", change.round_dp(2)),
+        Some(change) => format!("+{}%", change.round_dp(2)),
+        None => "unknown".to_string(),
+    }
+}
+
+/// Returns the percentage change going from one price to another, or None
+/// if the starting price is zero or the change is too large to represent.
+fn percent_change(from: Usd, to: Usd) -> Option<Decimal> {
+    let (from, to) = (from.get_decimal(), to.get_decimal());
+    to.checked_sub(from)?
+        .checked_div(from)?
+        .checked_mul(Decimal::new(100, 0))
+}
+
+/// The median of the given prices, or None if there are none. Fails if the
+/// two middle prices are too large to average.
+fn median(prices: &mut [Decimal]) -> Result<Option<Decimal>> {
+    if prices.is_empty() {
+        return Ok(None);
+    }
+    prices.sort();
+    let mid = prices.len() / 2;
+    if prices.len().is_multiple_of(2) {
+        let (low, high) = (prices[mid - 1], prices[mid]);
+        let sum = low
+            .checked_add(high)
+            .ok_or(OracleError::PriceOutOfRange { price: high })?;
+        Ok(Some(sum / Decimal::from(2)))
+    } else {
+        Ok(Some(prices[mid]))
+    }
+}
+
+/// Oracle prices are reported on chain scaled to 8 decimal places,
+/// regardless of the currency they are quoted in.
+const PRICE_DECIMALS: u32 = 8;
+/// The most decimal places --decimals may scale a price to
+const MAX_PRICE_DECIMALS: u32 = 18;
+
+fn to_oracle_price(usd: Usd) -> Result<u64> {
+    to_oracle_price_with(usd, Rounding::HalfUp, PRICE_DECIMALS)
+}
 
-    /// Commit the oracle price report to the API
-    #[structopt(long)]
-    commit: bool,
+fn to_oracle_price_with(usd: Usd, rounding: Rounding, decimals: u32) -> Result<u64> {
+    usd.get_decimal()
+        .round_dp_with_strategy(decimals, rounding.into())
+        .checked_mul(Decimal::from(10u64.pow(decimals)))
+        .and_then(|scaled| scaled.to_u64())
+        .ok_or_else(|| {
+            OracleError::PriceOutOfRange {
+                price: usd.get_decimal(),
+            }
+            .into()
+        })
 }
 
-impl Cmd {
-    pub async fn run(&self, opts: Opts) -> Result {
-        match self {
-            Cmd::Report(cmd) => cmd.run(opts).await,
+/// Refuses an oracle price that is not strictly positive, which would
+/// otherwise be reported on chain as a price of zero.
+fn check_price(price: u64) -> Result {
+    if price == 0 {
+        return Err(OracleError::PriceOutOfRange {
+            price: from_oracle_price(price).get_decimal(),
         }
+        .into());
     }
+    Ok(())
 }
 
-impl Report {
-    pub async fn run(&self, opts: Opts) -> Result {
-        let password = get_password(false)?;
-        let wallet = load_wallet(opts.files)?;
-        let keypair = wallet.decrypt(password.as_bytes())?;
-
-        let client = new_client(api_url(wallet.public_key.network));
-        let block_height = self.block.to_block(&client).await?;
-        let price = u64::from(self.price.to_usd().await?);
-        let mut txn = BlockchainTxnPriceOracleV1 {
-            public_key: keypair.public_key().into(),
-            price,
-            block_height,
-            signature: Vec::new(),
-        };
-        txn.signature = txn.sign(&keypair)?;
+fn from_oracle_price(price: u64) -> Usd {
+    from_oracle_price_with(price, PRICE_DECIMALS)
+}
 
-        let envelope = txn.in_envelope();
-        let status = maybe_submit_txn(self.commit, &client, &envelope).await?;
-        print_txn(&txn, &envelope, &status, opts.format)
+fn from_oracle_price_with(price: u64, decimals: u32) -> Usd {
+    Usd::new(Decimal::from_i128_with_scale(price as i128, decimals))
+}
+
+fn check_decimals(decimals: u32) -> Result {
+    if decimals > MAX_PRICE_DECIMALS {
+        bail!(
+            "Decimals {} is more than the maximum of {}",
+            decimals,
+            MAX_PRICE_DECIMALS
+        );
     }
+    Ok(())
 }
 
-fn print_txn(
-    txn: &BlockchainTxnPriceOracleV1,
-    envelope: &BlockchainTxn,
-    status: &Option<PendingTxnStatus>,
-    format: OutputFormat,
-) -> Result {
-    let encoded = envelope.to_b64()?;
-    match format {
-        OutputFormat::Table => {
-            ptable!(
-                ["Key", "Value"],
-                ["Block Height", txn.block_height],
-                ["Price", Usd::from(txn.price)],
-                ["Hash", status_str(status)]
-            );
+/// How a price is rounded to the decimal places reported on chain
+#[derive(Clone, Copy, Debug)]
+enum Rounding {
+    HalfUp,
+    HalfEven,
+    Down,
+}
 
-            print_footer(status)
+impl FromStr for Rounding {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "half-up" => Ok(Self::HalfUp),
+            "half-even" => Ok(Self::HalfEven),
+            "down" => Ok(Self::Down),
+            _ => bail!(
+                "Unknown rounding {}, expected one of half-up, half-even, down",
+                s
+            ),
         }
-        OutputFormat::Json => {
-            let table = json!({
-                "price": txn.price,
-                "block_height": txn.block_height,
-                "txn": encoded,
-                "hash": status_json(status)
-            });
-            print_json(&table)
+    }
+}
+
+impl From<Rounding> for RoundingStrategy {
+    fn from(rounding: Rounding) -> Self {
+        match rounding {
+            Rounding::HalfUp => RoundingStrategy::MidpointAwayFromZero,
+            Rounding::HalfEven => RoundingStrategy::MidpointNearestEven,
+            Rounding::Down => RoundingStrategy::ToZero,
         }
     }
 }
@@ -102,9 +1596,9 @@ enum Block {
 }
 
 impl FromStr for Block {
-    type Err = Box<dyn std::error::Error>;
+    type Err = anyhow::Error;
 
-    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+    fn from_str(s: &str) -> Result<Self> {
         match s {
             "auto" => Ok(Block::Auto),
             _ => Ok(Block::Height(s.parse()?)),
@@ -113,14 +1607,176 @@ impl FromStr for Block {
 }
 
 impl Block {
-    async fn to_block(self, client: &Client) -> Result<u64> {
+    async fn to_block(self, api: &impl OracleApi) -> Result<u64> {
         match self {
-            Block::Auto => Ok(blocks::height(client).await?),
+            Block::Auto => api.height().await,
             Block::Height(height) => Ok(height),
         }
     }
 }
 
+/// Upper bound on how long a rate limited fetch waits before its next
+/// attempt, whatever the source asks for
+const MAX_RATE_LIMIT_PAUSE: Duration = Duration::from_secs(60);
+
+/// Upper bound --retry-backoff doubles the delay between fetch attempts up
+/// to, unless the --retry-interval-ms itself is longer
+const MAX_BACKOFF_DELAY: Duration = Duration::from_secs(60);
+
+/// Error for a price source that answered with HTTP 429 Too Many Requests
+#[derive(Debug)]
+struct RateLimited {
+    source: &'static str,
+    retry_after: Option<Duration>,
+}
+
+impl RateLimited {
+    /// How long to wait before the next attempt. Honors the source's
+    /// Retry-After, and otherwise backs off exponentially from the retry
+    /// interval with up to half again of random jitter.
+    fn pause(&self, delay: Duration, attempt: u32) -> Duration {
+        if let Some(retry_after) = self.retry_after {
+            return retry_after.min(MAX_RATE_LIMIT_PAUSE);
+        }
+        let backoff = delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(MAX_RATE_LIMIT_PAUSE);
+        let jitter = rand::thread_rng().gen_range(0..=backoff.as_millis() as u64 / 2);
+        backoff + Duration::from_millis(jitter)
+    }
+}
+
+impl fmt::Display for RateLimited {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} is rate limiting requests", self.source)
+    }
+}
+
+impl std::error::Error for RateLimited {}
+
+/// Errors in looking up, checking or reporting an oracle price. These are
+/// returned wrapped in the crate's result error and can be recovered with
+/// downcast_ref.
+#[derive(Debug)]
+pub enum OracleError {
+    /// A price source answered with an unsuccessful HTTP status
+    SourceHttp {
+        source: &'static str,
+        status: reqwest::StatusCode,
+    },
+    /// A price source could not be reached or rate limited every attempt
+    SourceUnavailable { source: &'static str },
+    /// A price source answered with an error, such as for an unknown symbol
+    SourceApi {
+        source: &'static str,
+        message: String,
+    },
+    /// A price source response did not contain a usable price
+    SourceParse {
+        source: &'static str,
+        reason: String,
+    },
+    /// Every price source given was skipped with --disable
+    NoSources,
+    /// A price source does not quote prices in the --currency
+    UnsupportedCurrency {
+        source: &'static str,
+        currency: String,
+    },
+    /// The price can not be reported as an oracle price
+    PriceOutOfRange { price: Decimal },
+    /// The price deviates from the current oracle price by more than
+    /// --max-deviation
+    PriceDeviation {
+        price: Usd,
+        current: Usd,
+        deviation: Decimal,
+        max_deviation: Decimal,
+    },
+    /// The block height is too far from the current chain height
+    BlockOutOfRange { height: u64, tip: u64 },
+    /// A price source quoted a price older than --max-staleness-secs
+    StalePrice { source: &'static str, age_secs: u64 },
+    /// The API did not accept the signed report
+    SubmitFailed,
+}
+
+/// Process exit code for a price source that failed to give a price
+pub const EXIT_SOURCE_FAILED: i32 = 3;
+/// Process exit code for a price that can not be reported
+pub const EXIT_PRICE_OUT_OF_RANGE: i32 = 4;
+/// Process exit code for a block height too far from the chain height
+pub const EXIT_BLOCK_OUT_OF_RANGE: i32 = 5;
+/// Process exit code for a report the API did not accept
+pub const EXIT_SUBMIT_FAILED: i32 = 6;
+
+impl OracleError {
+    /// The process exit code for the error, so scripts can tell failures
+    /// apart. Other errors exit with 1.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::SourceHttp { .. }
+            | Self::SourceUnavailable { .. }
+            | Self::SourceApi { .. }
+            | Self::SourceParse { .. }
+            | Self::NoSources
+            | Self::UnsupportedCurrency { .. }
+            | Self::StalePrice { .. } => EXIT_SOURCE_FAILED,
+            Self::PriceOutOfRange { .. } | Self::PriceDeviation { .. } => EXIT_PRICE_OUT_OF_RANGE,
+            Self::BlockOutOfRange { .. } => EXIT_BLOCK_OUT_OF_RANGE,
+            Self::SubmitFailed => EXIT_SUBMIT_FAILED,
+        }
+    }
+}
+
+impl fmt::Display for OracleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::SourceHttp { source, status } => write!(f, "{} returned HTTP {}", source, status),
+            Self::SourceUnavailable { source } => {
+                write!(f, "Failed to fetch a price from {}", source)
+            }
+            Self::SourceApi { source, message } => write!(f, "{}: {}", source, message),
+            Self::SourceParse { source, reason } => {
+                write!(f, "Invalid response from {}: {}", source, reason)
+            }
+            Self::NoSources => f.write_str("All price sources are disabled"),
+            Self::UnsupportedCurrency { source, currency } => {
+                write!(f, "Price source {} does not quote {}", source, currency)
+            }
+            Self::PriceOutOfRange { price } => {
+                write!(f, "Refusing to report an oracle price of {}", price)
+            }
+            Self::PriceDeviation {
+                price,
+                current,
+                deviation,
+                max_deviation,
+            } => write!(
+                f,
+                "Price {} deviates {}% from current oracle price {} (max {}%). Use --force to commit anyway",
+                price,
+                deviation.round_dp(2),
+                current,
+                max_deviation
+            ),
+            Self::BlockOutOfRange { height, tip } => write!(
+                f,
+                "Block {} is far {} tip {}. Use --force to report anyway",
+                height,
+                if height < tip { "behind" } else { "ahead of" },
+                tip
+            ),
+            Self::StalePrice { source, age_secs } => {
+                write!(f, "{} quoted a price {}s old", source, age_secs)
+            }
+            Self::SubmitFailed => f.write_str("Failed to submit the report"),
+        }
+    }
+}
+
+impl std::error::Error for OracleError {}
+
 #[derive(Debug)]
 enum Price {
     CoinGecko,
@@ -128,54 +1784,367 @@ enum Price {
     BinanceUs,
     BinanceInt,
     Ftx,
+    Kraken,
+    Coinbase,
+    CoinMarketCap,
+    GateIo,
+    KuCoin,
+    Okx,
+    Bitfinex,
+    Gemini,
+    Huobi,
+    Custom { url: String, path: String },
     Usd(Usd),
 }
 
 impl Price {
-    async fn to_usd(&self) -> Result<Usd> {
+    /// The name of the price source, or "manual" for a given price
+    fn name(&self) -> &'static str {
+        match self {
+            Self::CoinGecko => "coingecko",
+            Self::Bilaxy => "bilaxy",
+            Self::BinanceUs => "binance-us",
+            Self::BinanceInt => "binance-int",
+            Self::Ftx => "ftx",
+            Self::Kraken => "kraken",
+            Self::Coinbase => "coinbase",
+            Self::CoinMarketCap => "cmc",
+            Self::GateIo => "gateio",
+            Self::KuCoin => "kucoin",
+            Self::Okx => "okx",
+            Self::Bitfinex => "bitfinex",
+            Self::Gemini => "gemini",
+            Self::Huobi => "huobi",
+            Self::Custom { .. } => "custom",
+            Self::Usd(_) => "manual",
+        }
+    }
+
+    /// Fetches the price, retrying as given in the fetch options. With
+    /// verbose set every failed attempt is reported on stderr.
+    async fn fetch(&self, opts: &FetchOpts, verbose: bool) -> Result<Usd> {
+        if !opts.is_usd() && !matches!(self, Self::CoinGecko | Self::Custom { .. } | Self::Usd(_)) {
+            return Err(OracleError::UnsupportedCurrency {
+                source: self.name(),
+                currency: opts.currency.clone(),
+            }
+            .into());
+        }
+        let client = opts.client()?;
+        let interval = Duration::from_millis(opts.retry_interval_ms);
+        let mut delay = interval;
+        let mut attempt = 1;
+        loop {
+            if let Some(limit) = opts.rate_limit.iter().find(|l| l.source == self.name()) {
+                limit.acquire().await;
+            }
+            let result = self.fetch_once(&client, opts).await;
+            if let (Err(err), true) = (&result, verbose) {
+                eprintln!(
+                    "{} attempt {}/{} failed: {}",
+                    self.name(),
+                    attempt,
+                    opts.retry_attempts,
+                    err
+                );
+            }
+            match result {
+                Ok(usd) => return Ok(usd),
+                Err(err) if attempt >= opts.retry_attempts => {
+                    if err.is::<OracleError>() {
+                        return Err(err);
+                    }
+                    return Err(err.context(OracleError::SourceUnavailable {
+                        source: self.name(),
+                    }));
+                }
+                Err(err) => {
+                    let pause = match err.downcast_ref::<RateLimited>() {
+                        Some(limited) => limited.pause(delay, attempt),
+                        None => delay,
+                    };
+                    tokio::time::sleep(pause).await;
+                    if opts.retry_backoff {
+                        delay = delay.saturating_mul(2).min(MAX_BACKOFF_DELAY).max(interval);
+                    }
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
+    /// Fetches the price --sample-count times over the --sample-window and
+    /// returns the median of the prices fetched. Fails only if every fetch
+    /// fails.
+    async fn sample(&self, opts: &FetchOpts, verbose: bool) -> Result<Usd> {
+        if opts.sample_count <= 1 {
+            return self.fetch(opts, verbose).await;
+        }
+        let interval = Duration::from_secs(opts.sample_window) / (opts.sample_count - 1);
+        let mut prices = Vec::with_capacity(opts.sample_count as usize);
+        let mut last_err = None;
+        for sample in 0..opts.sample_count {
+            if sample > 0 {
+                tokio::time::sleep(interval).await;
+            }
+            match self.fetch(opts, verbose).await {
+                Ok(usd) => prices.push(usd.get_decimal()),
+                Err(err) => last_err = Some(err),
+            }
+        }
+        match (median(&mut prices)?, last_err) {
+            (Some(price), _) => Ok(Usd::new(price)),
+            (None, Some(err)) => Err(err),
+            (None, None) => unreachable!("no samples taken"),
+        }
+    }
+
+    async fn fetch_once(&self, client: &reqwest::Client, opts: &FetchOpts) -> Result<Usd> {
+        if let Self::Usd(v) = self {
+            return Ok(*v);
+        }
+        let response = self.request(client, opts)?.send().await?;
+        let status = response.status();
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            let retry_after = response
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.trim().parse().ok())
+                .map(Duration::from_secs);
+            return Err(RateLimited {
+                source: self.name(),
+                retry_after,
+            }
+            .into());
+        }
+        if !status.is_success() {
+            return Err(OracleError::SourceHttp {
+                source: self.name(),
+                status,
+            }
+            .into());
+        }
+        let json: serde_json::Value = response.json().await.map_err(|err| self.parse_error(err))?;
+        let usd = self.parse(&json, opts)?;
+        if let (Some(max), Some(quoted_ms)) = (opts.max_staleness_secs, self.quote_time(&json)) {
+            let now_ms = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as u64;
+            let age_secs = now_ms.saturating_sub(quoted_ms) / 1000;
+            if age_secs > max {
+                return Err(OracleError::StalePrice {
+                    source: self.name(),
+                    age_secs,
+                }
+                .into());
+            }
+        }
+        if self.is_usdt_quoted() {
+            let price = usd.get_decimal();
+            return price
+                .checked_mul(opts.usdt_usd)
+                .map(Usd::new)
+                .ok_or_else(|| OracleError::PriceOutOfRange { price }.into());
+        }
+        Ok(usd)
+    }
+
+    /// The time in milliseconds since the epoch at which a price lookup
+    /// service response was quoted, for services that report it
+    fn quote_time(&self, json: &serde_json::Value) -> Option<u64> {
+        match self {
+            Self::BinanceInt => json["closeTime"].as_u64(),
+            Self::Huobi => json["ts"].as_u64(),
+            _ => None,
+        }
+    }
+
+    fn is_usdt_quoted(&self) -> bool {
+        matches!(
+            self,
+            Self::BinanceInt | Self::GateIo | Self::KuCoin | Self::Okx | Self::Huobi
+        )
+    }
+
+    fn request(
+        &self,
+        client: &reqwest::Client,
+        opts: &FetchOpts,
+    ) -> Result<reqwest::RequestBuilder> {
+        if let Self::Usd(_) = self {
+            bail!("A given price has no price lookup service");
+        }
+        let request = client.get(self.url(opts));
+        match self {
+            Self::CoinMarketCap => Ok(request.header("X-CMC_PRO_API_KEY", opts.cmc_key()?)),
+            _ => Ok(request),
+        }
+    }
+
+    /// The request URL for a price lookup service for the symbol given in
+    /// the fetch options.
+    fn url(&self, opts: &FetchOpts) -> String {
+        let symbol = opts.asset_symbol();
         match self {
+            Self::CoinGecko => format!(
+                "https://api.coingecko.com/api/v3/coins/{}",
+                opts.coingecko_coin_id()
+            ),
+            Self::Bilaxy => format!("https://newapi.bilaxy.com/v1/valuation?currency={}", symbol),
+            Self::BinanceUs => format!(
+                "https://api.binance.us/api/v3/ticker/price?symbol={}USD",
+                symbol
+            ),
+            Self::BinanceInt => format!(
+                "https://{}/api/v3/avgPrice?symbol={}USDT",
+                opts.binance_int_host, symbol
+            ),
+            Self::Ftx => format!("https://ftx.com/api/markets/{}/USD", symbol),
+            Self::Kraken => format!("https://api.kraken.com/0/public/Ticker?pair={}USD", symbol),
+            Self::Coinbase => format!("https://api.coinbase.com/v2/prices/{}-USD/spot", symbol),
+            Self::CoinMarketCap => format!(
+                "https://pro-api.coinmarketcap.com/v2/cryptocurrency/quotes/latest?symbol={}",
+                symbol
+            ),
+            Self::GateIo => format!(
+                "https://api.gateio.ws/api/v4/spot/tickers?currency_pair={}_USDT",
+                symbol
+            ),
+            Self::KuCoin => format!(
+                "https://api.kucoin.com/api/v1/market/orderbook/level1?symbol={}-USDT",
+                symbol
+            ),
+            Self::Okx => format!(
+                "https://www.okx.com/api/v5/market/ticker?instId={}-USDT",
+                symbol
+            ),
+            Self::Bitfinex => format!("https://api-pub.bitfinex.com/v2/ticker/t{}USD", symbol),
+            Self::Gemini => format!(
+                "https://api.gemini.com/v1/pubticker/{}usd",
+                symbol.to_lowercase()
+            ),
+            Self::Huobi => format!(
+                "https://api.huobi.pro/market/detail/merged?symbol={}usdt",
+                symbol.to_lowercase()
+            ),
+            Self::Custom { url, .. } => url.clone(),
+            Self::Usd(_) => String::new(),
+        }
+    }
+
+    /// Extracts the price from a price lookup service response.
+    fn parse(&self, json: &serde_json::Value, opts: &FetchOpts) -> Result<Usd> {
+        if let Some(message) = self.api_error(json) {
+            return Err(OracleError::SourceApi {
+                source: self.name(),
+                message,
+            }
+            .into());
+        }
+        let path = match self {
             Self::CoinGecko => {
-                let response =
-                    reqwest::get("https://api.coingecko.com/api/v3/coins/helium").await?;
-                let json: serde_json::Value = response.json().await?;
-                let amount = &json["market_data"]["current_price"]["usd"].to_string();
-                Ok(Usd::from_str(amount)?)
-            }
-            Self::Bilaxy => {
-                let response =
-                    reqwest::get("https://newapi.bilaxy.com/v1/valuation?currency=HNT").await?;
-                let json: serde_json::Value = response.json().await?;
-                let amount = &json["HNT"]["usd_value"]
-                    .as_str()
-                    .ok_or_else(|| anyhow!("No USD value found"))?;
-                Ok(Usd::from_str(amount)?)
-            }
-            Self::BinanceUs => {
-                let response =
-                    reqwest::get("https://api.binance.us/api/v3/ticker/price?symbol=HNTUSD")
-                        .await?;
-                let json: serde_json::Value = response.json().await?;
-                let amount = &json["price"]
-                    .as_str()
-                    .ok_or_else(|| anyhow!("No USD value found"))?;
-                Ok(Usd::from_str(amount)?)
-            }
-            Self::BinanceInt => {
-                let response =
-                    reqwest::get("https://api.binance.us/api/v3/avgPrice?symbol=HNTUSDT").await?;
-                let json: serde_json::Value = response.json().await?;
-                let amount = &json["price"]
-                    .as_str()
-                    .ok_or_else(|| anyhow!("No USD value found"))?;
-                Ok(Usd::from_str(amount)?)
-            }
-            Self::Ftx => {
-                let response = reqwest::get("https://ftx.com/api/markets/HNT/USD").await?;
-                let json: serde_json::Value = response.json().await?;
-                let amount = &json["result"]["price"].to_string();
-                Ok(Usd::from_str(amount)?)
-            }
-            Self::Usd(v) => Ok(*v),
+                format!("market_data.current_price.{}", opts.currency.to_lowercase())
+            }
+            Self::Bilaxy => format!("{}.usd_value", opts.asset_symbol()),
+            Self::BinanceUs | Self::BinanceInt => "price".to_string(),
+            Self::Ftx => "result.price".to_string(),
+            Self::Kraken => format!("result.{}USD.c[0]", opts.asset_symbol()),
+            Self::Coinbase => "data.amount".to_string(),
+            Self::CoinMarketCap => format!("data.{}[0].quote.USD.price", opts.asset_symbol()),
+            Self::GateIo => "[0].last".to_string(),
+            Self::KuCoin => "data.price".to_string(),
+            Self::Okx => "data[0].last".to_string(),
+            Self::Bitfinex => "[6]".to_string(),
+            Self::Gemini => "last".to_string(),
+            Self::Huobi => "tick.close".to_string(),
+            Self::Custom { path, .. } => path.clone(),
+            Self::Usd(v) => return Ok(*v),
+        };
+        parse_json_path(json, &path).map_err(|err| self.parse_error(err))
+    }
+
+    /// The error a price lookup service reports in an otherwise successful
+    /// response, for example for an unknown symbol. Checked before looking
+    /// for the price since an error response may still carry stale or
+    /// empty price fields.
+    fn api_error(&self, json: &serde_json::Value) -> Option<String> {
+        let message = |value: &serde_json::Value| match value {
+            serde_json::Value::String(message) => message.clone(),
+            serde_json::Value::Null => json.to_string(),
+            value => value.to_string(),
+        };
+        match self {
+            Self::Kraken => json["error"]
+                .as_array()
+                .filter(|errors| !errors.is_empty())
+                .map(|_| message(&json["error"][0])),
+            Self::Coinbase => json
+                .get("errors")
+                .map(|errors| message(&errors[0]["message"])),
+            // Tickers are positional arrays, errors are ["error", code, message]
+            Self::Bitfinex => (json[0] == "error").then(|| message(&json[2])),
+            Self::KuCoin => (json["code"] != "200000").then(|| message(&json["msg"])),
+            Self::Okx => (json["code"] != "0").then(|| message(&json["msg"])),
+            Self::Gemini => (json["result"] == "error").then(|| message(&json["message"])),
+            Self::Huobi => (json["status"] != "ok").then(|| message(&json["err-msg"])),
+            _ => None,
+        }
+    }
+
+    fn parse_error(&self, reason: impl fmt::Display) -> Error {
+        OracleError::SourceParse {
+            source: self.name(),
+            reason: reason.to_string(),
+        }
+        .into()
+    }
+}
+
+/// Parses the price at the given path, naming the path in any error so a
+/// changed response schema is easy to spot.
+fn parse_json_path(json: &serde_json::Value, path: &str) -> Result<Usd> {
+    let value = json_path(json, path).ok_or_else(|| anyhow!("missing {}", path))?;
+    let amount =
+        json_amount(value).ok_or_else(|| anyhow!("{} is {} rather than a price", path, value))?;
+    parse_literal(&amount).map_err(|_| anyhow!("{} is {} rather than a price", path, value))
+}
+
+/// Walks a dotted and bracketed path like "data[0].last" into a json value.
+fn json_path<'a>(json: &'a serde_json::Value, path: &str) -> Option<&'a serde_json::Value> {
+    let mut value = json;
+    for segment in path.split('.').filter(|s| !s.is_empty()) {
+        let (key, indices) = segment.split_at(segment.find('[').unwrap_or(segment.len()));
+        if !key.is_empty() {
+            value = value.get(key)?;
+        }
+        for index in indices.split(']').filter(|s| !s.is_empty()) {
+            let index: usize = index.strip_prefix('[')?.parse().ok()?;
+            value = value.get(index)?;
+        }
+    }
+    Some(value)
+}
+
+/// Returns the string form of a json string or number value. Strings are
+/// trimmed of whitespace and any quotes around the number.
+fn json_amount(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::String(s) => Some(s.trim().trim_matches('"').trim().to_string()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+impl fmt::Display for Price {
+    /// Formats the price the way it would be given on the command line. A
+    /// precision, as in "{:.2}", applies to literal prices.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Custom { url, path } => write!(f, "{}={}", url, path),
+            Self::Usd(v) => match f.precision() {
+                Some(precision) => write!(f, "{:.*}", precision, v.get_decimal()),
+                None => write!(f, "{}", v.get_decimal()),
+            },
+            _ => f.write_str(self.name()),
         }
     }
 }
@@ -192,13 +2161,726 @@ impl FromStr for Price {
             "binance-us" => Ok(Self::BinanceUs),
             "binance-int" => Ok(Self::BinanceInt),
             "ftx" => Ok(Self::Ftx),
-            _ => {
-                let data = Decimal::from_str(s).or_else(|_| Decimal::from_scientific(s))?;
-                Ok(Self::Usd(Usd::new(data.round_dp_with_strategy(
-                    8,
-                    RoundingStrategy::MidpointAwayFromZero,
-                ))))
+            "kraken" => Ok(Self::Kraken),
+            "coinbase" => Ok(Self::Coinbase),
+            "cmc" => Ok(Self::CoinMarketCap),
+            "gateio" => Ok(Self::GateIo),
+            "kucoin" => Ok(Self::KuCoin),
+            "okx" => Ok(Self::Okx),
+            "bitfinex" => Ok(Self::Bitfinex),
+            "gemini" => Ok(Self::Gemini),
+            "huobi" => Ok(Self::Huobi),
+            _ if s.starts_with("http") => {
+                let (url, path) = s
+                    .rsplit_once('=')
+                    .ok_or_else(|| anyhow!("Custom price source must be <url>=<path>"))?;
+                Ok(Self::Custom {
+                    url: url.to_string(),
+                    path: path.to_string(),
+                })
             }
+            _ if s.starts_with("file:") => {
+                let contents = fs::read_to_string(&s["file:".len()..])?;
+                Ok(Self::Usd(parse_literal(contents.trim())?))
+            }
+            _ if s.chars().all(|c| c.is_ascii_alphabetic() || c == '-') => {
+                match suggest_source(s) {
+                    Some(name) => bail!("Unknown price source {}, did you mean {}?", s, name),
+                    None => bail!(
+                        "Unknown price source {}, expected one of {}",
+                        s,
+                        SOURCE_NAMES.join(", ")
+                    ),
+                }
+            }
+            _ => Ok(Self::Usd(parse_literal(s)?)),
+        }
+    }
+}
+
+/// The names of the supported price lookup services
+const SOURCE_NAMES: &[&str] = &[
+    "coingecko",
+    "bilaxy",
+    "binance",
+    "binance-us",
+    "binance-int",
+    "ftx",
+    "kraken",
+    "coinbase",
+    "cmc",
+    "gateio",
+    "kucoin",
+    "okx",
+    "bitfinex",
+    "gemini",
+    "huobi",
+];
+
+/// Returns the source name closest to the given misspelled one, if any is
+/// close enough to be a likely typo.
+fn suggest_source(s: &str) -> Option<&'static str> {
+    SOURCE_NAMES
+        .iter()
+        .map(|name| (edit_distance(s, name), *name))
+        .filter(|(distance, _)| *distance <= 2)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, name)| name)
+}
+
+/// Levenshtein distance between two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitute = prev + usize::from(ca != *cb);
+            prev = row[j + 1];
+            row[j + 1] = substitute.min(prev + 1).min(row[j] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// Parses a literal price. The price is kept at full precision so that it
+/// is rounded only once, by the --rounding strategy.
+fn parse_literal(s: &str) -> Result<Usd> {
+    let data = Decimal::from_str(s).or_else(|_| Decimal::from_scientific(s))?;
+    Ok(Usd::new(data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        format::{self, Format},
+        pwhash::PwHash,
+    };
+
+    fn fetch_opts() -> FetchOpts {
+        FetchOpts::default()
+    }
+
+    /// A uniquely named file in the temporary directory, removed when
+    /// dropped even if the test fails
+    struct TempPath(PathBuf);
+
+    impl TempPath {
+        fn new(name: &str) -> Self {
+            Self(std::env::temp_dir().join(format!(
+                "helium-wallet-test-{}-{}",
+                name,
+                rand::random::<u64>()
+            )))
+        }
+
+        fn as_str(&self) -> &str {
+            self.0.to_str().expect("utf-8 temp path")
+        }
+    }
+
+    impl Drop for TempPath {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    fn test_wallet(keypair: &Keypair, password: &str) -> Wallet {
+        let format = format::Basic {
+            pwhash: PwHash::argon2id13_default(),
+        };
+        Wallet::encrypt(keypair, password.as_bytes(), Format::Basic(format)).expect("wallet")
+    }
+
+    /// An API at a fixed chain height that records submitted transactions
+    struct MockApi {
+        height: u64,
+        submitted: std::sync::Mutex<Vec<BlockchainTxn>>,
+    }
+
+    impl OracleApi for MockApi {
+        async fn height(&self) -> Result<u64> {
+            Ok(self.height)
+        }
+
+        async fn current_price(&self) -> Result<Usd> {
+            bail!("no current price")
+        }
+
+        async fn submit(&self, envelope: &BlockchainTxn) -> Result<PendingTxnStatus> {
+            self.submitted.lock().unwrap().push(envelope.clone());
+            Ok(PendingTxnStatus {
+                hash: "hash".to_string(),
+            })
+        }
+
+        async fn is_cleared(&self, _hash: &str) -> bool {
+            true
+        }
+    }
+
+    fn assert_parse(price: Price, json: serde_json::Value, expected: &str) {
+        let usd = price.parse(&json, &fetch_opts()).expect("parse price");
+        assert_eq!(usd.get_decimal(), Decimal::from_str(expected).unwrap());
+    }
+
+    #[test]
+    fn source_urls() {
+        let opts = fetch_opts();
+        assert_eq!(
+            Price::CoinGecko.url(&opts),
+            "https://api.coingecko.com/api/v3/coins/helium"
+        );
+        assert_eq!(
+            Price::Kraken.url(&opts),
+            "https://api.kraken.com/0/public/Ticker?pair=HNTUSD"
+        );
+        assert_eq!(
+            Price::BinanceInt.url(&opts),
+            "https://api.binance.com/api/v3/avgPrice?symbol=HNTUSDT"
+        );
+
+        let opts = FetchOpts::from_iter(&["oracle", "--symbol", "iot"]);
+        assert_eq!(
+            Price::CoinGecko.url(&opts),
+            "https://api.coingecko.com/api/v3/coins/helium-iot"
+        );
+        assert_eq!(
+            Price::KuCoin.url(&opts),
+            "https://api.kucoin.com/api/v1/market/orderbook/level1?symbol=IOT-USDT"
+        );
+
+        let opts =
+            FetchOpts::from_iter(&["oracle", "--symbol", "xyz", "--coingecko-id", "xyz-coin"]);
+        assert_eq!(
+            Price::CoinGecko.url(&opts),
+            "https://api.coingecko.com/api/v3/coins/xyz-coin"
+        );
+    }
+
+    #[test]
+    fn parse_sources() {
+        assert_parse(
+            Price::CoinGecko,
+            json!({"market_data": {"current_price": {"usd": 2.13, "eur": 1.9}}}),
+            "2.13",
+        );
+        assert_parse(Price::Bilaxy, json!({"HNT": {"usd_value": "2.13"}}), "2.13");
+        assert_parse(
+            Price::BinanceUs,
+            json!({"symbol": "HNTUSD", "price": "2.13000000"}),
+            "2.13",
+        );
+        assert_parse(
+            Price::BinanceInt,
+            json!({"mins": 5, "price": "2.13"}),
+            "2.13",
+        );
+        assert_parse(
+            Price::Ftx,
+            json!({"success": true, "result": {"price": 2.13}}),
+            "2.13",
+        );
+        assert_parse(
+            Price::Kraken,
+            json!({"error": [], "result": {"HNTUSD": {"c": ["2.13000", "1.5"]}}}),
+            "2.13",
+        );
+        assert_parse(
+            Price::Coinbase,
+            json!({"data": {"base": "HNT", "currency": "USD", "amount": "2.13"}}),
+            "2.13",
+        );
+        assert_parse(
+            Price::CoinMarketCap,
+            json!({"data": {"HNT": [{"quote": {"USD": {"price": 2.13}}}]}}),
+            "2.13",
+        );
+        assert_parse(
+            Price::GateIo,
+            json!([{"currency_pair": "HNT_USDT", "last": "2.13"}]),
+            "2.13",
+        );
+        assert_parse(
+            Price::KuCoin,
+            json!({"code": "200000", "data": {"price": "2.13"}}),
+            "2.13",
+        );
+        assert_parse(
+            Price::Okx,
+            json!({"code": "0", "data": [{"instId": "HNT-USDT", "last": "2.13"}]}),
+            "2.13",
+        );
+        assert_parse(
+            Price::Bitfinex,
+            json!([2.12, 100.0, 2.14, 120.0, 0.01, 0.0047, 2.13, 5000.0, 2.2, 2.0]),
+            "2.13",
+        );
+        assert_parse(
+            Price::Gemini,
+            json!({"bid": "2.12", "ask": "2.14", "last": "2.13"}),
+            "2.13",
+        );
+        assert_parse(
+            Price::Huobi,
+            json!({"status": "ok", "tick": {"close": 2.13, "open": 2.1}}),
+            "2.13",
+        );
+    }
+
+    #[test]
+    fn unknown_source() {
+        let err = Price::from_str("coingeko").unwrap_err();
+        assert!(err.to_string().contains("did you mean coingecko"));
+        let err = Price::from_str("nothing").unwrap_err();
+        assert!(err.to_string().contains("expected one of"));
+        assert_eq!(edit_distance("kraken", "kraken"), 0);
+        assert_eq!(edit_distance("krakn", "kraken"), 1);
+        assert!(matches!(Price::from_str("2.13"), Ok(Price::Usd(_))));
+    }
+
+    #[test]
+    fn rate_limit_pause() {
+        let delay = Duration::from_secs(1);
+        let limited = RateLimited {
+            source: "kraken",
+            retry_after: Some(Duration::from_secs(5)),
+        };
+        assert_eq!(limited.pause(delay, 1), Duration::from_secs(5));
+        let limited = RateLimited {
+            source: "kraken",
+            retry_after: Some(Duration::from_secs(3600)),
+        };
+        assert_eq!(limited.pause(delay, 1), MAX_RATE_LIMIT_PAUSE);
+        let limited = RateLimited {
+            source: "kraken",
+            retry_after: None,
+        };
+        let pause = limited.pause(delay, 2);
+        assert!(pause >= Duration::from_secs(4) && pause <= Duration::from_secs(6));
+    }
+
+    #[test]
+    fn parse_scientific() {
+        assert_parse(Price::BinanceUs, json!({"price": " 2.13e0 "}), "2.13");
+        assert_parse(
+            Price::Coinbase,
+            json!({"data": {"amount": "\"2.13\""}}),
+            "2.13",
+        );
+        assert_parse(
+            Price::CoinGecko,
+            json!({"market_data": {"current_price": {"usd": 2.13e-5}}}),
+            "0.0000213",
+        );
+    }
+
+    #[test]
+    fn parse_source_errors() {
+        let opts = fetch_opts();
+        assert!(Price::Kraken
+            .parse(&json!({"error": ["EQuery:Unknown asset pair"]}), &opts)
+            .is_err());
+        assert!(Price::Coinbase
+            .parse(&json!({"errors": [{"id": "not_found"}]}), &opts)
+            .is_err());
+        assert!(Price::Bitfinex
+            .parse(&json!(["error", 10020, "symbol: invalid"]), &opts)
+            .is_err());
+        assert!(Price::BinanceUs.parse(&json!({}), &opts).is_err());
+        let err = Price::CoinGecko
+            .parse(&json!({"market_data": {}}), &opts)
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid response from coingecko: missing market_data.current_price.usd"
+        );
+        let err = Price::BinanceUs
+            .parse(&json!({"price": null}), &opts)
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid response from binance-us: price is null rather than a price"
+        );
+        assert!(Price::Huobi
+            .parse(
+                &json!({"status": "error", "err-msg": "invalid symbol", "tick": {"close": 0}}),
+                &opts
+            )
+            .is_err());
+        let err = Price::Kraken
+            .parse(&json!({"error": ["EQuery:Unknown asset pair"]}), &opts)
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<OracleError>(),
+            Some(OracleError::SourceApi {
+                source: "kraken",
+                ..
+            })
+        ));
+        assert_eq!(err.to_string(), "kraken: EQuery:Unknown asset pair");
+        let err = Price::Coinbase
+            .parse(
+                &json!({"errors": [{"id": "not_found", "message": "Invalid base currency"}]}),
+                &opts,
+            )
+            .unwrap_err();
+        assert_eq!(err.to_string(), "coinbase: Invalid base currency");
+        assert!(Price::KuCoin
+            .parse(
+                &json!({"code": "400100", "msg": "symbol not exists"}),
+                &opts
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn roundtrip_oracle_price() {
+        for price in &[
+            "0",
+            "0.00000001",
+            "0.0099",
+            "2.13",
+            "1234.56789012",
+            "184467440737.09551615",
+        ] {
+            let usd = Usd::new(Decimal::from_str(price).unwrap());
+            let oracle_price = to_oracle_price(usd).expect("oracle price");
+            assert_eq!(
+                from_oracle_price(oracle_price).get_decimal(),
+                usd.get_decimal()
+            );
+        }
+    }
+
+    #[test]
+    fn oracle_price_rounding() {
+        let usd = Usd::new(Decimal::from_str("1.123456785").unwrap());
+        assert_eq!(to_oracle_price(usd).unwrap(), 112_345_679);
+        let usd = Usd::new(Decimal::from_str("1.123456785").unwrap());
+        assert_eq!(
+            to_oracle_price_with(usd, Rounding::HalfEven, PRICE_DECIMALS).unwrap(),
+            112_345_678
+        );
+        let usd = Usd::new(Decimal::from_str("1.123456789").unwrap());
+        assert_eq!(
+            to_oracle_price_with(usd, Rounding::Down, PRICE_DECIMALS).unwrap(),
+            112_345_678
+        );
+        let usd = Usd::new(Decimal::from_str("184467440737.09551616").unwrap());
+        assert!(to_oracle_price(usd).is_err());
+        let usd = Usd::new(Decimal::from_str("-1").unwrap());
+        let err = to_oracle_price(usd).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<OracleError>(),
+            Some(OracleError::PriceOutOfRange { .. })
+        ));
+    }
+
+    #[test]
+    fn oracle_price_decimals() {
+        let usd = Usd::new(Decimal::from_str("2.1234567").unwrap());
+        let price = to_oracle_price_with(usd, Rounding::HalfUp, 6).unwrap();
+        assert_eq!(price, 2_123_457);
+        assert_eq!(
+            from_oracle_price_with(price, 6).get_decimal(),
+            Decimal::from_str("2.123457").unwrap()
+        );
+        assert!(check_decimals(MAX_PRICE_DECIMALS + 1).is_err());
+    }
+
+    #[tokio::test]
+    async fn dry_run_without_wallet() {
+        let api = MockApi {
+            height: 1_000,
+            submitted: Default::default(),
+        };
+        let report =
+            Report::from_iter(&["report", "--price", "2.13", "--block", "auto", "--dry-run"]);
+        report
+            .report(&api, None, Network::MainNet, OutputFormat::Json, false)
+            .await
+            .expect("dry run");
+        assert!(api.submitted.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn commit_decimals_refused() {
+        let api = MockApi {
+            height: 1_000,
+            submitted: Default::default(),
+        };
+        let wallet = test_wallet(&Keypair::default(), "password");
+        let report = Report::from_iter(&[
+            "report",
+            "--price",
+            "2.13",
+            "--block",
+            "auto",
+            "--decimals",
+            "6",
+            "--commit",
+        ]);
+        assert!(report
+            .report(
+                &api,
+                Some(&wallet),
+                Network::MainNet,
+                OutputFormat::Json,
+                false
+            )
+            .await
+            .is_err());
+        assert!(api.submitted.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn zero_price_refused() {
+        let usd = Usd::new(Decimal::from_str("0.000000001").unwrap());
+        let price = to_oracle_price(usd).unwrap();
+        assert!(check_price(price).is_err());
+        assert!(check_price(1).is_ok());
+    }
+
+    #[tokio::test]
+    async fn cost_estimate_overflow() {
+        let opts = Opts::from_iter(&["wallet"]);
+        let estimate =
+            CostEstimate::from_iter(&["cost-estimate", "--delay", "0.00000000000000000000000001"]);
+        assert!(estimate.run(opts).await.is_err());
+    }
+
+    #[test]
+    fn deviation_exit_code() {
+        let current = Usd::new(Decimal::from(2));
+        let usd = Usd::new(Decimal::from(3));
+        assert!(check_deviation(Some(current), usd, Decimal::from(60)).is_ok());
+        let err = check_deviation(Some(current), usd, Decimal::from(5)).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<OracleError>()
+                .map(OracleError::exit_code),
+            Some(EXIT_PRICE_OUT_OF_RANGE)
+        );
+    }
+
+    #[test]
+    fn percent_change_overflow() {
+        let from = Usd::new(Decimal::from_str("2").unwrap());
+        let to = Usd::new(Decimal::from_str("2.5").unwrap());
+        assert_eq!(
+            percent_change(from, to),
+            Some(Decimal::from_str("25").unwrap())
+        );
+        assert_eq!(percent_change(Usd::new(Decimal::ZERO), to), None);
+        assert_eq!(percent_change(from, Usd::new(Decimal::MAX)), None);
+    }
+
+    #[tokio::test]
+    async fn sign_report() {
+        let api = MockApi {
+            height: 1_000,
+            submitted: Default::default(),
+        };
+        let keypair = Keypair::default();
+        let wallet = test_wallet(&keypair, "password");
+        let path = TempPath::new("password");
+        fs::write(&path.0, "password\n").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&path.0, fs::Permissions::from_mode(0o600)).unwrap();
         }
+        let report = Report::from_iter(&[
+            "report",
+            "--price",
+            "2.13",
+            "--block",
+            "auto",
+            "--password-file",
+            path.as_str(),
+            "--commit",
+        ]);
+        report
+            .report(
+                &api,
+                Some(&wallet),
+                Network::MainNet,
+                OutputFormat::Json,
+                false,
+            )
+            .await
+            .expect("committed report");
+
+        let mut submitted = api.submitted.lock().unwrap();
+        assert_eq!(submitted.len(), 1);
+        let envelope = submitted.pop().unwrap();
+        let submitted = BlockchainTxnPriceOracleV1::from_envelope(&envelope).unwrap();
+        assert_eq!(submitted.price, 213_000_000);
+        assert_eq!(submitted.block_height, 1_000);
+        let public_key = PublicKey::from_bytes(&submitted.public_key).unwrap();
+        assert_eq!(&public_key, keypair.public_key());
+        assert!(submitted.verify(&public_key, &submitted.signature).is_ok());
+    }
+
+    #[test]
+    fn deadband() {
+        let path = TempPath::new("deadband");
+        let state = ReportState {
+            block_height: 100,
+            price: 200_000_000,
+            timestamp: 0,
+        };
+        state.save(&path.0).unwrap();
+        let report = Report::from_iter(&[
+            "report",
+            "--price",
+            "2.01",
+            "--block",
+            "auto",
+            "--commit",
+            "--state-file",
+            path.as_str(),
+            "--deadband",
+            "1",
+        ]);
+        assert!(report.within_deadband(201_000_000));
+        assert!(!report.within_deadband(203_000_000));
+    }
+
+    #[test]
+    fn disabled_sources() {
+        let report = Report::from_iter(&[
+            "report",
+            "--price",
+            "binance",
+            "--fallback-price",
+            "kraken",
+            "--disable",
+            "binance-us",
+            "--block",
+            "auto",
+        ]);
+        assert!(report.is_disabled(&report.price));
+        assert!(!report.is_disabled(&report.fallback_price[0]));
+    }
+
+    #[tokio::test]
+    async fn all_sources_disabled() {
+        let report = Report::from_iter(&[
+            "report",
+            "--price",
+            "kraken",
+            "--disable",
+            "kraken",
+            "--block",
+            "auto",
+        ]);
+        let err = report.fetch_price(false).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<OracleError>(),
+            Some(OracleError::NoSources)
+        ));
+    }
+
+    #[test]
+    fn block_offset() {
+        let report = Report::from_iter(&[
+            "report",
+            "--price",
+            "2.13",
+            "--block",
+            "auto",
+            "--block-offset",
+            "-2",
+        ]);
+        assert_eq!(report.offset_block(100).unwrap(), 98);
+        assert!(report.offset_block(2).is_err());
+    }
+
+    #[test]
+    fn median_price() {
+        let mut prices: Vec<Decimal> = ["2.3", "2.1", "9.9"]
+            .iter()
+            .map(|p| Decimal::from_str(p).unwrap())
+            .collect();
+        assert_eq!(
+            median(&mut prices).unwrap(),
+            Some(Decimal::from_str("2.3").unwrap())
+        );
+        prices.pop();
+        assert_eq!(
+            median(&mut prices).unwrap(),
+            Some(Decimal::from_str("2.2").unwrap())
+        );
+        assert_eq!(median(&mut []).unwrap(), None);
+        assert!(median(&mut [Decimal::MAX, Decimal::MAX]).is_err());
+    }
+
+    #[test]
+    fn fetch_opts_setters() {
+        let opts = FetchOpts::default()
+            .symbol("iot")
+            .retry_attempts(3)
+            .rate_limit("coingecko", 30)
+            .unwrap();
+        assert_eq!(opts.asset_symbol(), "IOT");
+        assert_eq!(opts.retry_attempts, 3);
+        assert_eq!(opts.rate_limit[0].source, "coingecko");
+        assert!(FetchOpts::default().rate_limit("coingecko", 0).is_err());
+    }
+
+    #[test]
+    fn parse_rate_limit() {
+        let limit = RateLimit::from_str("binance=30").unwrap();
+        assert_eq!(limit.source, "binance-us");
+        assert_eq!(limit.per_min, 30);
+        assert!(RateLimit::from_str("coingecko=0").is_err());
+        assert!(RateLimit::from_str("coingecko").is_err());
+    }
+
+    #[tokio::test]
+    async fn rate_limit_window() {
+        let limit = RateLimit {
+            source: "test",
+            per_min: 2,
+        };
+        limit.acquire().await;
+        limit.acquire().await;
+        assert!(
+            tokio::time::timeout(Duration::from_millis(100), limit.acquire())
+                .await
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn quote_csv_field() {
+        assert_eq!(csv_field("kraken"), "kraken");
+        assert_eq!(
+            csv_field("https://x/?a=1,2=data.last"),
+            "\"https://x/?a=1,2=data.last\""
+        );
+        assert_eq!(
+            csv_field(r#"price is {"a":1,"b":2}"#),
+            r#""price is {""a"":1,""b"":2}""#
+        );
+    }
+
+    #[test]
+    fn source_quote_time() {
+        let json = json!({"status": "ok", "ts": 1_650_000_000_000u64, "tick": {"close": 2.13}});
+        assert_eq!(Price::Huobi.quote_time(&json), Some(1_650_000_000_000));
+        assert_eq!(Price::BinanceUs.quote_time(&json!({"price": "2.13"})), None);
+    }
+
+    #[test]
+    fn walk_json_path() {
+        let json = json!({"data": [{"last": "1"}, {"last": "2"}], "nested": {"a": [[3]]}});
+        assert_eq!(json_path(&json, "data[1].last"), Some(&json!("2")));
+        assert_eq!(json_path(&json, "nested.a[0][0]"), Some(&json!(3)));
+        assert_eq!(json_path(&json, "data[2].last"), None);
+        assert_eq!(json_path(&json, "missing"), None);
     }
 }
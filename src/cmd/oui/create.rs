@@ -86,12 +86,24 @@ impl Create {
             key if key == Some(wallet_key) || key.is_none() => {
                 // Payer is the wallet submit if ready to commit
                 let status = maybe_submit_txn(self.commit, &client, &envelope).await?;
-                print_txn(&txn, &envelope, &status, opts.format)
+                print_txn(
+                    &txn,
+                    &envelope,
+                    &status,
+                    wallet.public_key.network,
+                    opts.format,
+                )
             }
             _ => {
                 // Payer is something else.
                 // can't commit this transaction but we can display it
-                print_txn(&txn, &envelope, &None, opts.format)
+                print_txn(
+                    &txn,
+                    &envelope,
+                    &None,
+                    wallet.public_key.network,
+                    opts.format,
+                )
             }
         }
     }
@@ -101,6 +113,7 @@ fn print_txn(
     txn: &BlockchainTxnOuiV1,
     envelope: &BlockchainTxn,
     status: &Option<PendingTxnStatus>,
+    network: Network,
     format: OutputFormat,
 ) -> Result {
     match format {
@@ -116,14 +129,15 @@ fn print_txn(
                 ["Hash", status_str(status)]
             );
 
-            print_footer(status)
+            print_footer(status, network)
         }
-        OutputFormat::Json => {
+        OutputFormat::Json | OutputFormat::Csv | OutputFormat::Yaml => {
             let table = json!({
                 "previous_oui": txn.oui,
                 "addresses": map_addresses(txn.addresses.clone(), |v| v.to_string())?,
                 "requested_subnet_size": txn.requested_subnet_size,
                 "hash": status_json(status),
+                "explorer_url": status_explorer_json(status, network),
                 "txn": envelope.to_b64()?,
             });
 
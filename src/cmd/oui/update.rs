@@ -161,7 +161,13 @@ impl Update {
         let envelope = txn.in_envelope();
 
         let status = maybe_submit_txn(commit, &client, &envelope).await?;
-        print_txn(&txn, &envelope, &status, opts.format)
+        print_txn(
+            &txn,
+            &envelope,
+            &status,
+            wallet.public_key.network,
+            opts.format,
+        )
     }
 }
 
@@ -169,6 +175,7 @@ fn print_txn(
     txn: &BlockchainTxnRoutingV1,
     envelope: &BlockchainTxn,
     status: &Option<PendingTxnStatus>,
+    network: Network,
     format: OutputFormat,
 ) -> Result {
     let update = match txn.update.as_ref().unwrap() {
@@ -193,13 +200,14 @@ fn print_txn(
                 ["Update", update],
                 ["Hash", status_str(status)]
             );
-            print_footer(status)
+            print_footer(status, network)
         }
-        OutputFormat::Json => {
+        OutputFormat::Json | OutputFormat::Csv | OutputFormat::Yaml => {
             let table = json!({
                 "last_oui": txn.oui,
                 "update": update,
                 "hash": status_json(status),
+                "explorer_url": status_explorer_json(status, network),
                 "txn": envelope.to_b64()?,
             });
             print_json(&table)
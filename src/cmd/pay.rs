@@ -103,7 +103,13 @@ impl Cmd {
 
         let envelope = txn.in_envelope();
         let status = maybe_submit_txn(self.commit(), &client, &envelope).await?;
-        print_txn(&txn, &envelope, &status, opts.format)
+        print_txn(
+            &txn,
+            &envelope,
+            &status,
+            wallet.public_key.network,
+            opts.format,
+        )
     }
 
     fn collect_payments(&self) -> Result<Vec<Payment>> {
@@ -155,6 +161,7 @@ fn print_txn(
     txn: &BlockchainTxnPaymentV2,
     envelope: &BlockchainTxn,
     status: &Option<PendingTxnStatus>,
+    network: Network,
     format: OutputFormat,
 ) -> Result {
     match format {
@@ -177,9 +184,9 @@ fn print_txn(
                 ["Hash", status_str(status)]
             );
 
-            print_footer(status)
+            print_footer(status, network)
         }
-        OutputFormat::Json => {
+        OutputFormat::Json | OutputFormat::Csv | OutputFormat::Yaml => {
             let mut payments = Vec::with_capacity(txn.payments.len());
             for payment in txn.payments.clone() {
                 payments.push(json!({
@@ -193,6 +200,7 @@ fn print_txn(
                 "fee": txn.fee,
                 "nonce": txn.nonce,
                 "hash": status_json(status),
+                "explorer_url": status_explorer_json(status, network),
                 "txn": envelope.to_b64()?,
             });
             print_json(&table)
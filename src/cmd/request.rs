@@ -74,7 +74,7 @@ impl Burn {
 
 fn print_request(request: &serde_json::Value, format: OutputFormat) -> Result {
     match format {
-        OutputFormat::Json => print_json(request),
+        OutputFormat::Json | OutputFormat::Csv | OutputFormat::Yaml => print_json(request),
         OutputFormat::Table => {
             print_qr(&serde_json::to_string(&request)?)?;
             Ok(())
@@ -57,7 +57,13 @@ impl Transfer {
 
         let envelope = txn.in_envelope();
         let status = maybe_submit_txn(self.commit, &client, &envelope).await?;
-        print_txn(&txn, &envelope, &status, opts.format)
+        print_txn(
+            &txn,
+            &envelope,
+            &status,
+            wallet.public_key.network,
+            opts.format,
+        )
     }
 }
 
@@ -65,6 +71,7 @@ fn print_txn(
     txn: &BlockchainTxnSecurityExchangeV1,
     envelope: &BlockchainTxn,
     status: &Option<PendingTxnStatus>,
+    network: Network,
     format: OutputFormat,
 ) -> Result {
     let payee = PublicKey::from_bytes(&txn.payee)?.to_string();
@@ -78,15 +85,16 @@ fn print_txn(
                 ["Nonce", txn.nonce],
                 ["Hash", status_str(status)]
             );
-            print_footer(status)
+            print_footer(status, network)
         }
-        OutputFormat::Json => {
+        OutputFormat::Json | OutputFormat::Csv | OutputFormat::Yaml => {
             let table = json!({
                 "payee": payee,
                 "amount": txn.amount,
                     "fee": txn.fee,
              "nonce": txn.nonce,
                 "hash": status_json(status),
+                "explorer_url": status_explorer_json(status, network),
                 "txn": envelope.to_b64()?,
             });
             print_json(&table)
@@ -44,7 +44,7 @@ fn print_keypair(keypair: &Keypair, format: OutputFormat) -> Result {
             ptable!(["Key", "Value"], ["Address", address], ["Name", name]);
             Ok(())
         }
-        OutputFormat::Json => {
+        OutputFormat::Json | OutputFormat::Csv | OutputFormat::Yaml => {
             let table = json!({
                 "address": address,
                 "name": name,
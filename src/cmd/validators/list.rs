@@ -72,7 +72,7 @@ fn print_results(
             }
             print_table(&table)
         }
-        OutputFormat::Json => {
+        OutputFormat::Json | OutputFormat::Csv | OutputFormat::Yaml => {
             let mut table = Vec::with_capacity(results.len());
             for (address, result) in results {
                 let mut table_validators = vec![];
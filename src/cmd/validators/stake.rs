@@ -84,7 +84,13 @@ impl Cmd {
             let txn = self.mk_txn(&keypair, &fee_config, &validator)?;
             let envelope = txn.in_envelope();
             let status = maybe_submit_txn(self.commit(), &client, &envelope).await?;
-            print_txn(&envelope, &txn, &status, &opts.format)?
+            print_txn(
+                &envelope,
+                &txn,
+                &status,
+                wallet.public_key.network,
+                &opts.format,
+            )?
         }
         Ok(())
     }
@@ -141,6 +147,7 @@ fn print_txn(
     envelope: &BlockchainTxn,
     txn: &BlockchainTxnStakeValidatorV1,
     status: &Option<PendingTxnStatus>,
+    network: Network,
     format: &OutputFormat,
 ) -> Result {
     let validator = PublicKey::from_bytes(&txn.address)?.to_string();
@@ -156,15 +163,16 @@ fn print_txn(
                 "Once staked an owner cannot access the staked amount until\n\
                 250,000 blocks (approx. 5 months) after unstaking."]
             );
-            print_footer(status)
+            print_footer(status, network)
         }
-        OutputFormat::Json => {
+        OutputFormat::Json | OutputFormat::Csv | OutputFormat::Yaml => {
             let table = json!({
                 "validator" : validator,
                 "fee": txn.fee,
                 "staking_fee": txn.stake,
                 "txn": envelope.to_b64()?,
-                "hash": status_json(status)
+                "hash": status_json(status),
+                "explorer_url": status_explorer_json(status, network)
             });
             print_json(&table)
         }
@@ -130,7 +130,13 @@ impl Create {
 
         let envelope = txn.in_envelope();
         let status = maybe_submit_txn(self.commit, &client, &envelope).await?;
-        print_txn(Some(&envelope), &txn, &status, opts.format)
+        print_txn(
+            Some(&envelope),
+            &txn,
+            &status,
+            wallet.public_key.network,
+            opts.format,
+        )
     }
 }
 
@@ -155,7 +161,13 @@ impl Accept {
 
         let envelope = txn.in_envelope();
         let status = maybe_submit_txn(self.commit, &client, &envelope).await?;
-        print_txn(Some(&envelope), &txn, &status, opts.format)
+        print_txn(
+            Some(&envelope),
+            &txn,
+            &status,
+            wallet.public_key.network,
+            opts.format,
+        )
     }
 }
 
@@ -163,6 +175,7 @@ fn print_txn(
     envelope: Option<&BlockchainTxn>,
     txn: &BlockchainTxnTransferValidatorStakeV1,
     status: &Option<PendingTxnStatus>,
+    network: Network,
     format: OutputFormat,
 ) -> Result {
     let old_address = PublicKey::from_bytes(&txn.old_address)?.to_string();
@@ -185,9 +198,9 @@ fn print_txn(
                 ["Amount (HNT)", Hnt::from(txn.payment_amount)],
                 ["Hash", status_str(status)]
             );
-            print_footer(status)
+            print_footer(status, network)
         }
-        OutputFormat::Json => {
+        OutputFormat::Json | OutputFormat::Csv | OutputFormat::Yaml => {
             let mut table = json!({
                 "old_address" : old_address,
                 "new_address" : new_address,
@@ -195,7 +208,8 @@ fn print_txn(
                 "new_owner" : new_owner,
                 "fee": txn.fee,
                 "amount": Hnt::from(txn.payment_amount),
-                "hash": status_json(status)
+                "hash": status_json(status),
+                "explorer_url": status_explorer_json(status, network)
             });
             if let Some(envelope) = envelope {
                 table["txn"] = envelope.to_b64()?.into();
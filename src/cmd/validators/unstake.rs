@@ -69,7 +69,13 @@ impl Cmd {
 
         let envelope = txn.in_envelope();
         let status = maybe_submit_txn(self.commit, &client, &envelope).await?;
-        print_txn(&envelope, &txn, &status, opts.format)
+        print_txn(
+            &envelope,
+            &txn,
+            &status,
+            wallet.public_key.network,
+            opts.format,
+        )
     }
 }
 
@@ -77,6 +83,7 @@ fn print_txn(
     envelope: &BlockchainTxn,
     txn: &BlockchainTxnUnstakeValidatorV1,
     status: &Option<PendingTxnStatus>,
+    network: Network,
     format: OutputFormat,
 ) -> Result {
     let validator = PublicKey::from_bytes(&txn.address)?.to_string();
@@ -92,15 +99,16 @@ fn print_txn(
                 "After unstaking, a validator can not access the staked amount\n\
                 nor earn rewards for 250,000 blocks (approx. five months)."]
             );
-            print_footer(status)
+            print_footer(status, network)
         }
-        OutputFormat::Json => {
+        OutputFormat::Json | OutputFormat::Csv | OutputFormat::Yaml => {
             let table = json!({
                 "validator" : validator,
                 "fee": txn.fee,
                 "stake_amount": txn.stake_amount,
                 "txn": envelope.to_b64()?,
-                "hash": status_json(status)
+                "hash": status_json(status),
+                "explorer_url": status_explorer_json(status, network)
             });
             print_json(&table)
         }
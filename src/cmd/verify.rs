@@ -52,7 +52,7 @@ pub fn print_result(
             }
             print_table(&table)
         }
-        OutputFormat::Json => {
+        OutputFormat::Json | OutputFormat::Csv | OutputFormat::Yaml => {
             let mut table = json!({
                 "address": address,
                 "sharded": wallet.is_sharded(),
@@ -8,6 +8,7 @@ pub mod format;
 pub mod keypair;
 pub mod memo;
 pub mod mnemonic;
+pub mod oracle;
 pub mod pwhash;
 pub mod result;
 pub mod staking;
@@ -1,11 +1,11 @@
 use helium_wallet::{
     cmd::{
-        balance, burn, commit, create, hotspots, htlc, info, multisig, oracle, oui, pay, request,
-        securities, upgrade, validators, vars, verify, Opts,
+        balance, burn, commit, config_args, create, hotspots, htlc, info, multisig, oracle, oui,
+        pay, request, securities, upgrade, validators, vars, verify, Opts,
     },
     result::Result,
 };
-use std::process;
+use std::{env, process};
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
@@ -40,14 +40,25 @@ pub enum Cmd {
 
 #[tokio::main]
 async fn main() {
-    let cli = Cli::from_args();
+    let args = match config_args(env::args_os().collect()) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("error: {:?}", e);
+            process::exit(1);
+        }
+    };
+    let cli = Cli::from_iter(args);
     if let Err(e) = run(cli).await {
         eprintln!("error: {:?}", e);
-        process::exit(1);
+        let code = e
+            .downcast_ref::<oracle::OracleError>()
+            .map_or(1, oracle::OracleError::exit_code);
+        process::exit(code);
     }
 }
 
 async fn run(cli: Cli) -> Result {
+    cli.opts.apply_output();
     match cli.cmd {
         Cmd::Info(cmd) => cmd.run(cli.opts).await,
         Cmd::Verify(cmd) => cmd.run(cli.opts).await,
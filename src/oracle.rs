@@ -0,0 +1 @@
+pub use crate::cmd::oracle::{fetch_price, FetchOpts, OracleError};